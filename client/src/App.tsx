@@ -18,37 +18,170 @@ interface RawNode {
   id: number;
   name: string;
   kind: NodeKind;
+  visibility: 'public' | 'crate' | 'restricted' | 'private';
+  depth: number;
+  siblingIndex: number;
+  declarationOrder: number;
+  publiclyReachable: boolean;
+  changed: boolean;
+  churn: number | null;
+  line: number | null;
   children: number[];
+  edges: RawEdge[];
+  bounds: string[];
+  whereClause: Predicate[];
+  aliases: string[];
+  docHidden: boolean;
+  lifetimes: string[];
+  definitionPath: string | null;
+  duplicateOf: number | null;
+  transformedBy: string[];
+  memberCount: number | null;
+  annotations: Record<string, unknown>;
   documentation: string;
+  docExamples: string[];
   sourceCode: string;
 }
 
+interface Predicate {
+  bounded: string;
+  bounds: string[];
+  traits: string[];
+  rendered: string;
+}
+
+interface ManifestDependency {
+  name: string;
+  rename: string | null;
+  req: string;
+  kind: 'normal' | 'dev' | 'build';
+  optional: boolean;
+  usesDefaultFeatures: boolean;
+  features: string[];
+  target: string | null;
+}
+
 type NodeKind =
   | { type: 'workspace'; content: { path: string } }
-  | { type: 'package'; content: { path: string } }
-  | { type: 'module'; content: { path: string } }
-  | { type: 'struct'; content: { fields: Field[] } }
-  | { type: 'enum'; content: { variants: string[] } }
+  | {
+      type: 'package';
+      content: {
+        path: string;
+        noStd: boolean;
+        dependencies: ManifestDependency[];
+      };
+    }
+  | { type: 'target'; content: { kind: string; path: string } }
+  | {
+      type: 'module';
+      content: {
+        path: string;
+        macroInvocations: number;
+        isFacade: boolean;
+        externalCrates: string[];
+        isSkipped: boolean;
+      };
+    }
+  | {
+      type: 'struct';
+      content: {
+        fields: Field[];
+        derives: string[];
+        markerTraits: string[];
+        nonExhaustive: boolean;
+      };
+    }
+  | {
+      type: 'enum';
+      content: {
+        variants: string[];
+        derives: string[];
+        markerTraits: string[];
+        nonExhaustive: boolean;
+      };
+    }
+  | { type: 'union'; content: { fields: Field[] } }
   | {
       type: 'function';
-      content: { arguments: Field[]; returnType: string | null };
+      content: {
+        arguments: Field[];
+        returnType: string | null;
+        panicCount: number;
+        todoCount: number;
+        unimplementedCount: number;
+        role: 'normal' | 'test' | 'bench' | 'exampleMain';
+      };
     }
   | { type: 'const'; content: { ty: string; value: string } }
-  | { type: 'macro'; content: { macroRules: boolean } }
+  | { type: 'macro'; content: { macroRules: boolean; ruleCount: number } }
   | { type: 'static'; content: { ty: string; mutability: boolean } }
-  | { type: 'trait'; content: { isAuto: boolean; isUnsafe: boolean } }
+  | {
+      type: 'trait';
+      content: {
+        isAuto: boolean;
+        isUnsafe: boolean;
+        objectSafe: boolean | null;
+        externalImplementors: string[];
+        providedMethods: string[];
+      };
+    }
   | { type: 'traitAlias'; content: { generics: string } }
   | { type: 'type'; content: { generics: string } }
+  | {
+      type: 'impl';
+      content: {
+        selfType: string;
+        traitName: string | null;
+        isUnsafe: boolean;
+      };
+    }
+  | { type: 'verbatim'; content: { tokens: string } }
+  | { type: 'external'; content: { id: number } }
+  | { type: 'removed' }
   | { type: 'unknown' };
 
+interface RawEdge {
+  target: number;
+  kind:
+    | 'uses'
+    | 'derives'
+    | 'bound'
+    | 'implements'
+    | 'selfType'
+    | 'dependency'
+    | 'devDependency'
+    | 'buildDependency';
+  scope: 'intraModule' | 'interModule' | 'interPackage';
+}
+
 interface Field {
   name: string;
   typeName: string;
 }
 
 interface RawGraph {
+  schemaVersion: number;
+  expanded: boolean;
   root: number;
   nodes: RawNode[];
+  warnings: RawWarning[];
+  reexports: Record<string, string[]>;
+}
+
+interface RawWarning {
+  category:
+    | 'annotation'
+    | 'duplicate'
+    | 'expand'
+    | 'git'
+    | 'parse'
+    | 'read'
+    | 'path'
+    | 'size'
+    | 'verbatim'
+    | 'walk';
+  message: string;
+  path: string;
 }
 
 interface CustomNode extends Node {
@@ -60,20 +193,37 @@ interface CustomGraphData extends GraphData {
   nodes: CustomNode[];
 }
 
+const SCHEMA_VERSION = 1;
+
 const Graph: React.FC = () => {
   const [graph, setGraph] = useState<CustomGraphData | null>(null);
   const [selectedNode, setSelectedNode] = useState<CustomNode | null>(null);
   const [graphHeight, setGraphHeight] = useState<string>('100vh');
   const [isSheetOpen, setIsSheetOpen] = useState(false);
+  const [error, setError] = useState<string | null>(null);
 
   useEffect(() => {
     fetch('/api/graph')
-      .then((response) => response.json())
+      .then(async (response) => {
+        const data = await response.json();
+        if (!response.ok) {
+          throw new Error(data.error ?? response.statusText);
+        }
+        return data;
+      })
       .then((data: RawGraph) => {
+        if (data.schemaVersion !== SCHEMA_VERSION) {
+          console.warn(
+            `Expected graph schema version ${SCHEMA_VERSION}, got ${data.schemaVersion}`
+          );
+        }
         const graphData = transformData(data);
         setGraph(graphData);
       })
-      .catch((error) => console.error('Error fetching graph data:', error));
+      .catch((error) => {
+        console.error('Error fetching graph data:', error);
+        setError(error.message);
+      });
 
     const updateHeight = () => {
       setGraphHeight(`${window.innerHeight}px`);
@@ -86,21 +236,30 @@ const Graph: React.FC = () => {
   }, []);
 
   const transformData = useCallback((data: RawGraph): CustomGraphData => {
-    const nodes: CustomNode[] = data.nodes.map((node) => ({
-      id: node.id,
-      label: getNodeLabel(node),
-      title: generateNodeTooltip(node),
-      color: getNodeColor(node.kind),
-      rawData: node,
-    }));
-
-    const edges: Edge[] = data.nodes.flatMap((node) =>
-      node.children.map((childId) => ({
+    const nodes: CustomNode[] = data.nodes
+      .filter((node) => node.kind.type !== 'removed')
+      .map((node) => ({
+        id: node.id,
+        label: getNodeLabel(node),
+        title: generateNodeTooltip(node),
+        color: getNodeColor(node.kind),
+        rawData: node,
+      }));
+
+    const edges: Edge[] = data.nodes.flatMap((node) => [
+      ...node.children.map((childId) => ({
         from: node.id,
         to: childId,
         arrows: 'to',
-      }))
-    );
+      })),
+      ...node.edges.map((edge) => ({
+        from: node.id,
+        to: edge.target,
+        arrows: 'to',
+        dashes: true,
+        title: edge.kind,
+      })),
+    ]);
 
     return { nodes, edges };
   }, []);
@@ -126,6 +285,9 @@ const Graph: React.FC = () => {
       case 'package':
         details = `Package`;
         break;
+      case 'target':
+        details = `Target (${node.kind.content.kind})`;
+        break;
       case 'module':
         details = `Module`;
         break;
@@ -135,6 +297,9 @@ const Graph: React.FC = () => {
       case 'enum':
         details = `Enum with ${node.kind.content.variants.length} variants`;
         break;
+      case 'union':
+        details = `Union with ${node.kind.content.fields.length} fields`;
+        break;
       case 'function':
         details = `Function with ${node.kind.content.arguments.length} arguments`;
         break;
@@ -156,6 +321,18 @@ const Graph: React.FC = () => {
       case 'type':
         details = `Type`;
         break;
+      case 'impl':
+        details = `Implementation`;
+        break;
+      case 'verbatim':
+        details = `Unparsed item`;
+        break;
+      case 'external':
+        details = `External`;
+        break;
+      case 'removed':
+        details = `Removed`;
+        break;
       case 'unknown':
         details = `Unknown`;
         break;
@@ -169,12 +346,16 @@ const Graph: React.FC = () => {
         return '#FF6B6B';
       case 'package':
         return '#4ECDC4';
+      case 'target':
+        return '#F7B267';
       case 'module':
         return '#97C2FC';
       case 'struct':
         return '#FFCCCB';
       case 'enum':
         return '#90EE90';
+      case 'union':
+        return '#FFE4B5';
       case 'function':
         return '#FFD700';
       case 'const':
@@ -189,6 +370,14 @@ const Graph: React.FC = () => {
         return '#E6E6FA';
       case 'type':
         return '#F08080';
+      case 'impl':
+        return '#B0C4DE';
+      case 'verbatim':
+        return '#DCDCDC';
+      case 'external':
+        return '#C0C0C0';
+      case 'removed':
+        return '#F5F5F5';
       case 'unknown':
         return '#D3D3D3';
       default:
@@ -276,6 +465,7 @@ const Graph: React.FC = () => {
         </p>
         {(rawData.kind.type === 'workspace' ||
           rawData.kind.type === 'package' ||
+          rawData.kind.type === 'target' ||
           rawData.kind.type === 'module') && (
           <p>
             <strong>Path:</strong> {rawData.kind.content.path}
@@ -388,6 +578,18 @@ const Graph: React.FC = () => {
             <p>{rawData.documentation}</p>
           </div>
         )}
+        {rawData.docExamples.length > 0 && (
+          <div>
+            <p>
+              <strong>Examples:</strong>
+            </p>
+            {rawData.docExamples.map((example: string, index: number) => (
+              <pre key={index} className='overflow-auto'>
+                {example}
+              </pre>
+            ))}
+          </div>
+        )}
         {rawData.sourceCode && (
           <div>
             <p>
@@ -426,6 +628,7 @@ const Graph: React.FC = () => {
   return (
     <div className='relative h-screen w-screen overflow-hidden'>
       <div className='absolute inset-0 pr-[400px]'>
+        {error && <p className='p-4 text-red-600'>{error}</p>}
         {graph && (
           <VisGraph
             graph={graph}
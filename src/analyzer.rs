@@ -0,0 +1,3159 @@
+use super::*;
+
+/// Attributes built into the compiler, which don't transform the item they
+/// annotate the way an attribute macro might.
+const BUILTIN_ATTRIBUTES: &[&str] = &[
+  "allow",
+  "automatically_derived",
+  "cfg",
+  "cfg_attr",
+  "cold",
+  "deny",
+  "deprecated",
+  "derive",
+  "doc",
+  "expect",
+  "export_name",
+  "forbid",
+  "global_allocator",
+  "ignore",
+  "inline",
+  "link",
+  "link_name",
+  "link_section",
+  "macro_export",
+  "macro_use",
+  "must_use",
+  "no_mangle",
+  "non_exhaustive",
+  "panic_handler",
+  "path",
+  "proc_macro",
+  "proc_macro_attribute",
+  "proc_macro_derive",
+  "repr",
+  "should_panic",
+  "target_feature",
+  "test",
+  "track_caller",
+  "used",
+  "warn",
+];
+
+/// Tools whose attributes, such as `rustfmt::skip`, are inert.
+const TOOL_ATTRIBUTES: &[&str] = &["clippy", "rustdoc", "rustfmt"];
+
+const MACRO_EXPANSION_DEPTH: usize = 8;
+
+/// Marker traits reported on types that derive or implement them.
+const MARKER_TRAITS: &[&str] = &["Copy", "Send", "Sync", "Unpin"];
+
+pub(crate) struct Analyzer<'a> {
+  bounds: Vec<(NodeId, NodeId, Vec<String>)>,
+  /// Lines changed since `config.since`, by file.
+  changes: HashMap<PathBuf, Vec<(usize, usize)>>,
+  config: &'a Config,
+  deadline: Option<Instant>,
+  /// Names that the current package's dependencies are referred to by.
+  dependencies: HashSet<String>,
+  derive_macros: HashMap<String, NodeId>,
+  derives: Vec<(NodeId, Vec<String>)>,
+  /// Number of item nodes added so far for the file being analyzed.
+  declaration_order: usize,
+  /// Module nodes for `mod` declarations without a body, whose items are in
+  /// a separate file module.
+  declarations: HashSet<NodeId>,
+  declared_modules: Vec<(PathBuf, PathBuf)>,
+  graph: Graph,
+  /// The node, module, self type, and trait, if any, of each `impl` block.
+  implementations: Vec<(NodeId, NodeId, String, Option<String>)>,
+  imports: Vec<(NodeId, Vec<Import>)>,
+  invocations: Vec<(NodeId, String, TokenStream, PathBuf)>,
+  macros: HashMap<String, MacroRules>,
+  /// Where files for the current module's `mod` declarations live, if they
+  /// should be followed.
+  module_dir: Option<PathBuf>,
+  profile: Profile,
+  traces: Vec<(Item, NodeId, NodeId)>,
+  /// Whether to count commits touching each definition, which requires
+  /// `config.git_churn` and a git repository.
+  track_churn: bool,
+}
+
+impl<'a> Analyzer<'a> {
+  pub(crate) fn new(config: &'a Config) -> Self {
+    Self {
+      bounds: Vec::new(),
+      changes: HashMap::new(),
+      config,
+      deadline: config.timeout.map(|timeout| Instant::now() + timeout),
+      dependencies: HashSet::new(),
+      derive_macros: HashMap::new(),
+      derives: Vec::new(),
+      declaration_order: 0,
+      declarations: HashSet::new(),
+      declared_modules: Vec::new(),
+      graph: Graph::default(),
+      implementations: Vec::new(),
+      imports: Vec::new(),
+      invocations: Vec::new(),
+      macros: HashMap::new(),
+      module_dir: None,
+      profile: Profile::default(),
+      traces: Vec::new(),
+      track_churn: false,
+    }
+  }
+
+  /// How long each phase of the last analysis took.
+  pub(crate) fn profile(&self) -> &Profile {
+    &self.profile
+  }
+
+  pub(crate) fn analyze(&mut self, crate_path: &Path) -> Result<Graph> {
+    let start = self.start()?;
+
+    // Paths like `.` have no file name to name a workspace after until
+    // they're made absolute.
+    let crate_path = &Self::canonicalize(crate_path)?;
+
+    let packages = self.packages(crate_path)?;
+
+    self.profile.metadata = start.elapsed();
+
+    if self.config.expand {
+      cargo_expand::check()?;
+      self.graph.expanded = true;
+    }
+
+    if let Some(since) = &self.config.since {
+      match git::changed_lines(crate_path, since) {
+        Ok(changes) => self.changes = changes,
+        Err(error) => self.warn(WarningCategory::Git, error, crate_path),
+      }
+    }
+
+    if self.config.git_churn {
+      match git::check_repository(crate_path) {
+        Ok(()) => self.track_churn = true,
+        Err(error) => self.warn(WarningCategory::Git, error, crate_path),
+      }
+    }
+
+    let is_proper_workspace = packages.len() > 1;
+
+    if is_proper_workspace {
+      self.graph.nodes.push(Node {
+        id: 0,
+        name: crate_path.file_name().map_or_else(
+          || crate_path.display().to_string(),
+          |name| name.to_string_lossy().into_owned(),
+        ),
+        kind: NodeKind::Workspace {
+          path: crate_path.to_path_buf(),
+        },
+        ..Default::default()
+      });
+    }
+
+    let mut package_ids = HashMap::new();
+
+    for package in &packages {
+      package_ids.insert(package.name.as_str(), self.graph.nodes.len());
+      self.handle_package(package, 0, is_proper_workspace)?;
+    }
+
+    // Even without dependency resolution, each package lists its manifest's
+    // dependencies, including those on other workspace members.
+    for package in &packages {
+      for dependency in &package.dependencies {
+        let Some(&target) = package_ids.get(dependency.name.as_str()) else {
+          continue;
+        };
+
+        let kind = match dependency.kind {
+          DependencyKind::Development => EdgeKind::DevDependency,
+          DependencyKind::Build => EdgeKind::BuildDependency,
+          _ => EdgeKind::Dependency,
+        };
+
+        self.graph.nodes[package_ids[package.name.as_str()]]
+          .add_edge(target, kind);
+      }
+    }
+
+    self.finish(start, Some(crate_path))
+  }
+
+  /// Whether each package that analyzing the crate or workspace at
+  /// `crate_path` would include is `#![no_std]`, keyed by name, read from
+  /// the inner attributes of its targets' root files alone.
+  pub(crate) fn no_std(
+    &self,
+    crate_path: &Path,
+  ) -> Result<BTreeMap<String, bool>> {
+    let packages = self.packages(&Self::canonicalize(crate_path)?)?;
+
+    Ok(
+      packages
+        .into_iter()
+        .map(|package| {
+          let no_std = package
+            .targets
+            .iter()
+            .filter(|target| {
+              self
+                .config
+                .bin
+                .as_ref()
+                .is_none_or(|bin| Self::is_bin(target, bin))
+            })
+            .filter_map(|target| fs::read_to_string(&target.src_path).ok())
+            .filter_map(|source| parse_file(&source).ok())
+            .any(|syntax| self.is_no_std(&syntax.attrs));
+
+          (package.name, no_std)
+        })
+        .collect(),
+    )
+  }
+
+  fn canonicalize(crate_path: &Path) -> Result<PathBuf> {
+    crate_path.canonicalize().map_err(|error| {
+      anyhow!("failed to find crate `{}`: {error}", crate_path.display())
+    })
+  }
+
+  /// The workspace members to analyze, from the crate's metadata, narrowed
+  /// down by [`Config::packages`] and [`Config::bin`].
+  fn packages(&self, crate_path: &Path) -> Result<Vec<Package>> {
+    let metadata = match &self.config.metadata {
+      Some(path) => Self::read_metadata(path)?,
+      None if !crate_path.join("Cargo.toml").is_file() => {
+        bail!("`{}` has no Cargo.toml", crate_path.display())
+      }
+      None => MetadataCommand::new()
+        .manifest_path(crate_path.join("Cargo.toml"))
+        .no_deps()
+        .exec()?,
+    };
+
+    let mut workspace_members = metadata
+      .workspace_members
+      .into_iter()
+      .collect::<HashSet<_>>();
+
+    if !self.config.packages.is_empty() {
+      for name in &self.config.packages {
+        if !metadata.packages.iter().any(|package| {
+          &package.name == name && workspace_members.contains(&package.id)
+        }) {
+          bail!("package `{name}` is not a member of the workspace");
+        }
+      }
+
+      workspace_members.retain(|id| {
+        metadata.packages.iter().any(|package| {
+          &package.id == id && self.config.packages.contains(&package.name)
+        })
+      });
+    }
+
+    if let Some(bin) = &self.config.bin {
+      workspace_members.retain(|id| {
+        metadata.packages.iter().any(|package| {
+          &package.id == id
+            && package
+              .targets
+              .iter()
+              .any(|target| Self::is_bin(target, bin))
+        })
+      });
+
+      if workspace_members.is_empty() {
+        bail!("no binary target named `{bin}` in the workspace");
+      }
+    }
+
+    Ok(
+      metadata
+        .packages
+        .into_iter()
+        .filter(|package| workspace_members.contains(&package.id))
+        .collect(),
+    )
+  }
+
+  /// Read an annotations file, a JSON object keyed by path pattern.
+  fn read_annotations(
+    path: &Path,
+  ) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let json = fs::read_to_string(path).map_err(|error| {
+      anyhow!("failed to read annotations `{}`: {error}", path.display())
+    })?;
+
+    serde_json::from_str(&json).map_err(|error| {
+      anyhow!(
+        "`{}` isn't a JSON object of annotations: {error}",
+        path.display()
+      )
+    })
+  }
+
+  /// Read the output of `cargo metadata` from `path`, checking that it's in
+  /// the only format version there is.
+  fn read_metadata(path: &Path) -> Result<Metadata> {
+    let json = fs::read_to_string(path).map_err(|error| {
+      anyhow!(
+        "failed to read cargo metadata `{}`: {error}",
+        path.display()
+      )
+    })?;
+
+    let value =
+      serde_json::from_str::<serde_json::Value>(&json).map_err(|error| {
+        anyhow!("`{}` isn't valid JSON: {error}", path.display())
+      })?;
+
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+      Some(1) => {}
+      Some(version) => bail!(
+        "`{}` has cargo metadata format version {version}, expected 1",
+        path.display()
+      ),
+      None => bail!(
+        "`{}` isn't cargo metadata: it has no format version",
+        path.display()
+      ),
+    }
+
+    serde_json::from_value(value).map_err(|error| {
+      anyhow!(
+        "`{}` doesn't match the cargo metadata format: {error}",
+        path.display()
+      )
+    })
+  }
+
+  /// Analyze the module tree rooted at `entry`, following `mod` declarations
+  /// as for a cargo target, under a target node named after the file.
+  pub(crate) fn analyze_entry(&mut self, entry: &Path) -> Result<Graph> {
+    let start = self.start()?;
+
+    let source = fs::read_to_string(entry).map_err(|error| {
+      anyhow!("failed to read entry file `{}`: {error}", entry.display())
+    })?;
+
+    if let Err(error) = parse_file(&source) {
+      bail!("failed to parse entry file `{}`: {error}", entry.display());
+    }
+
+    let entry = entry.canonicalize()?;
+
+    let dir = entry.parent().unwrap_or(&entry).to_path_buf();
+
+    let name = entry
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().into_owned())
+      .unwrap_or_default();
+
+    let mut profile = PackageProfile {
+      name: name.clone(),
+      ..Default::default()
+    };
+
+    self.graph.nodes.push(Node {
+      name,
+      kind: NodeKind::Target {
+        kind: "entry".into(),
+        path: entry.clone(),
+      },
+      ..Default::default()
+    });
+
+    self.handle_module_tree(&entry, 0, &dir, &mut profile)?;
+
+    self.profile.packages.push(profile);
+
+    self.finish(start, Some(&dir))
+  }
+
+  /// Analyze a single source file, whose module is the root of the graph and
+  /// whose `mod` declarations aren't followed.
+  pub(crate) fn analyze_source(
+    &mut self,
+    name: &str,
+    source: String,
+  ) -> Result<Graph> {
+    let start = self.start()?;
+
+    let mut profile = PackageProfile {
+      name: name.into(),
+      ..Default::default()
+    };
+
+    self.handle_source(Path::new(name), name.into(), source, &mut profile)?;
+
+    self.profile.packages.push(profile);
+
+    self.finish(start, None)
+  }
+
+  fn start(&mut self) -> Result<Instant> {
+    let start = Instant::now();
+
+    // Line numbers come from a source map that grows with every file parsed
+    // on this thread, so drop the one left by any earlier analysis.
+    proc_macro2::extra::invalidate_current_thread_spans();
+
+    for kind in &self.config.exclude_kinds {
+      NodeKind::validate(kind)?;
+    }
+
+    Ok(start)
+  }
+
+  /// Resolve references between the nodes added so far and tidy the graph,
+  /// relativizing file paths to `crate_path`, if any.
+  fn finish(
+    &mut self,
+    start: Instant,
+    crate_path: Option<&Path>,
+  ) -> Result<Graph> {
+    if self.config.expand_macros {
+      let expansion = Instant::now();
+      self.expand_macros()?;
+      self.profile.expansion = expansion.elapsed();
+    }
+
+    self.graph.inherit_module_visibility();
+
+    let paths = self.config.respect_visibility.then(|| self.graph.paths());
+
+    let packages = self
+      .graph
+      .nearest(|kind| matches!(kind, NodeKind::Package { .. }));
+
+    let impls = self.impls();
+
+    // Trace dependencies once every node exists, so that references to items
+    // declared later still resolve.
+    for (item, node_id, module_id) in take(&mut self.traces) {
+      self.check_deadline()?;
+
+      let tracing = Instant::now();
+
+      self.trace_dependencies(
+        &item,
+        node_id,
+        module_id,
+        &impls,
+        paths.as_deref(),
+      );
+
+      if let Some(package) = self
+        .profile
+        .packages
+        .iter_mut()
+        .find(|package| Some(package.id) == packages[node_id])
+      {
+        package.tracing += tracing.elapsed();
+      }
+    }
+
+    let resolution = Instant::now();
+
+    self.resolve_bounds();
+    self.resolve_derives();
+    self.resolve_implementations();
+
+    self.graph.reexports =
+      Reexports::new(&self.graph, take(&mut self.imports)).collect();
+
+    self.graph.mark_recursion();
+
+    self.graph.mark_publicly_reachable();
+
+    if self.config.qualify_types {
+      self.graph.qualify_types();
+    }
+
+    self.graph.scope_edges();
+
+    self.mark_facades();
+
+    if self.config.collapse_reexports {
+      self.collapse_reexports();
+    }
+
+    self.graph.filter(self.config);
+
+    self.graph.sort(self.config.sort);
+
+    self.graph.renumber(self.config.id_order);
+
+    self.graph.lay_out();
+
+    self.graph.mark_duplicates();
+
+    if let Some(path) = &self.config.annotations {
+      self.graph.annotate(&Self::read_annotations(path)?, path)?;
+    }
+
+    if !self.config.redact.is_empty() {
+      self.graph.redact(&self.config.redact);
+    }
+
+    if let Some(crate_path) = crate_path.filter(|_| !self.config.absolute_paths)
+    {
+      self.graph.relativize(&crate_path.canonicalize()?);
+    }
+
+    if self.config.strict && !self.graph.warnings.is_empty() {
+      bail!(
+        "analysis produced {} warning(s):\n{}",
+        self.graph.warnings.len(),
+        self
+          .graph
+          .warnings
+          .iter()
+          .map(|warning| format!("  {warning}"))
+          .collect::<Vec<String>>()
+          .join("\n")
+      );
+    }
+
+    self.profile.resolution = resolution.elapsed();
+    self.profile.total = start.elapsed();
+
+    Ok(take(&mut self.graph))
+  }
+
+  fn handle_package(
+    &mut self,
+    package: &Package,
+    parent_id: NodeId,
+    is_workspace: bool,
+  ) -> Result {
+    let package_id = self.graph.nodes.len();
+
+    let package_node = Node {
+      id: package_id,
+      name: package.name.clone(),
+      kind: NodeKind::Package {
+        path: package.manifest_path.parent().unwrap().to_path_buf().into(),
+        no_std: false,
+        dependencies: package.dependencies.iter().map(Into::into).collect(),
+      },
+      documentation: package.description.clone().unwrap_or_default(),
+      ..Default::default()
+    };
+
+    self.graph.nodes.push(package_node);
+
+    if is_workspace {
+      self.graph.nodes[parent_id].children.push(package_id);
+    }
+
+    let package_path = package.manifest_path.parent().unwrap().as_std_path();
+
+    self.dependencies = package
+      .dependencies
+      .iter()
+      .map(|dependency| {
+        dependency
+          .rename
+          .as_ref()
+          .unwrap_or(&dependency.name)
+          .replace('-', "_")
+      })
+      .collect();
+
+    let mut profile = PackageProfile {
+      name: package.name.clone(),
+      id: package_id,
+      ..Default::default()
+    };
+
+    match &self.config.src_dir {
+      Some(src_dir) => {
+        let src_path = package_path.join(src_dir);
+
+        if !src_path.is_dir() {
+          bail!(
+            "source directory `{}` does not exist in package `{}`",
+            src_path.display(),
+            package.name
+          );
+        }
+
+        let walk = Instant::now();
+        let files = self.walk(&src_path);
+        let files = self.without_disabled_modules(files);
+        profile.walk += walk.elapsed();
+
+        for file_path in &files {
+          self.check_deadline()?;
+
+          let module_name =
+            self.module_name(file_path, &src_path, package_path);
+
+          self.handle_file(file_path, module_name, package_id, &mut profile)?;
+        }
+      }
+      None => {
+        for target in &package.targets {
+          if self
+            .config
+            .bin
+            .as_ref()
+            .is_some_and(|bin| !Self::is_bin(target, bin))
+          {
+            continue;
+          }
+
+          self.handle_target(target, package_id, package_path, &mut profile)?;
+        }
+      }
+    }
+
+    self.profile.packages.push(profile);
+
+    Ok(())
+  }
+
+  /// Whether `target` is the binary target named `name`.
+  fn is_bin(target: &Target, name: &str) -> bool {
+    target.name == name && target.kind.iter().any(|kind| kind == "bin")
+  }
+
+  /// Analyze a target's root file and, unless only root modules are wanted,
+  /// every file its `mod` declarations lead to, under a node for the target.
+  /// An expanded target is instead a single module, with its submodules
+  /// inline.
+  fn handle_target(
+    &mut self,
+    target: &Target,
+    package_id: NodeId,
+    package_path: &Path,
+    profile: &mut PackageProfile,
+  ) -> Result {
+    let target_id = self.graph.nodes.len();
+
+    let root = target.src_path.as_std_path();
+
+    self.graph.nodes.push(Node {
+      id: target_id,
+      name: target.name.clone(),
+      kind: NodeKind::Target {
+        kind: target.kind.first().cloned().unwrap_or_default(),
+        path: root.to_path_buf(),
+      },
+      ..Default::default()
+    });
+
+    self.graph.nodes[package_id].children.push(target_id);
+
+    match self.expand_target(target, package_path) {
+      Some(source) => {
+        let module_name = self.module_name(
+          root,
+          root.parent().unwrap_or(package_path),
+          package_path,
+        );
+
+        let module_id =
+          self.handle_source(root, module_name, source, profile)?;
+
+        self.graph.nodes[target_id].children.push(module_id);
+      }
+      None => {
+        self.handle_module_tree(root, target_id, package_path, profile)?
+      }
+    }
+
+    if target.kind.iter().any(|kind| kind == "example") {
+      self.mark_example_main(target_id);
+    }
+
+    Ok(())
+  }
+
+  /// The source of `target` as expanded by `cargo expand`, if
+  /// [`Config::expand`] is set and the target can be expanded, warning if it
+  /// fails.
+  fn expand_target(
+    &mut self,
+    target: &Target,
+    package_path: &Path,
+  ) -> Option<String> {
+    if !self.config.expand {
+      return None;
+    }
+
+    match cargo_expand::target(&package_path.join("Cargo.toml"), target) {
+      Ok(source) => source,
+      Err(error) => {
+        self.warn(
+          WarningCategory::Expand,
+          error,
+          target.src_path.as_std_path(),
+        );
+        None
+      }
+    }
+  }
+
+  /// Give the `main` function in the root module of an example target the
+  /// [`FunctionRole::ExampleMain`] role.
+  fn mark_example_main(&mut self, target_id: NodeId) {
+    let Some(&root_id) = self.graph.nodes[target_id].children.first() else {
+      return;
+    };
+
+    for child in self.graph.nodes[root_id].children.clone() {
+      let node = &mut self.graph.nodes[child];
+
+      if let NodeKind::Function { role, .. } = &mut node.kind {
+        if node.name == "main" {
+          *role = FunctionRole::ExampleMain;
+        }
+      }
+    }
+  }
+
+  /// Analyze the root file of a module tree and, unless only root modules are
+  /// wanted, every file its `mod` declarations lead to, under `parent_id`.
+  fn handle_module_tree(
+    &mut self,
+    root: &Path,
+    parent_id: NodeId,
+    package_path: &Path,
+    profile: &mut PackageProfile,
+  ) -> Result {
+    let root_dir = root.parent().unwrap_or(package_path);
+
+    let walk = Instant::now();
+
+    // Files excluded by ignore rules are skipped even if a `mod` declaration
+    // refers to them.
+    let unignored = (!self.config.no_ignore && !self.config.root_module_only)
+      .then(|| {
+        self
+          .walk(root_dir)
+          .into_iter()
+          .collect::<HashSet<PathBuf>>()
+      });
+
+    profile.walk += walk.elapsed();
+
+    let mut files = vec![(root.to_path_buf(), root_dir.to_path_buf())];
+
+    let mut visited = HashSet::new();
+
+    let mut i = 0;
+
+    while let Some((file_path, module_dir)) = files.get(i).cloned() {
+      i += 1;
+
+      self.check_deadline()?;
+
+      if !visited.insert(file_path.clone()) {
+        continue;
+      }
+
+      let is_ignored = unignored.as_ref().is_some_and(|unignored| {
+        file_path != root
+          && file_path.starts_with(root_dir)
+          && !unignored.contains(&file_path)
+      });
+
+      if is_ignored {
+        continue;
+      }
+
+      let module_name = self.module_name(&file_path, root_dir, package_path);
+
+      self.module_dir = (!self.config.root_module_only).then_some(module_dir);
+
+      self.handle_file(&file_path, module_name, parent_id, profile)?;
+
+      self.module_dir = None;
+
+      files.extend(take(&mut self.declared_modules));
+    }
+
+    Ok(())
+  }
+
+  /// Read and parse a source file, adding a module node for it to `parent_id`
+  /// along with its items, or an empty placeholder if it's larger than
+  /// [`Config::max_file_size`].
+  fn handle_file(
+    &mut self,
+    file_path: &Path,
+    module_name: String,
+    parent_id: NodeId,
+    profile: &mut PackageProfile,
+  ) -> Result {
+    // Files that can't be measured are left to fail when they're read.
+    let size = fs::metadata(file_path).map_or(0, |metadata| metadata.len());
+
+    if let Some(max_file_size) =
+      self.config.max_file_size.filter(|&max| size > max)
+    {
+      self.warn(
+        WarningCategory::Size,
+        format!(
+          "skipped file of {size} bytes, larger than the limit of \
+           {max_file_size}"
+        ),
+        file_path,
+      );
+
+      let module_id =
+        self.handle_source(file_path, module_name, String::new(), profile)?;
+
+      if let NodeKind::Module { is_skipped, .. } =
+        &mut self.graph.nodes[module_id].kind
+      {
+        *is_skipped = true;
+      }
+
+      self.graph.nodes[parent_id].children.push(module_id);
+
+      return Ok(());
+    }
+
+    let file_content = match fs::read_to_string(file_path) {
+      Ok(file_content) => file_content,
+      Err(error) => {
+        self.warn(WarningCategory::Read, error, file_path);
+        return Ok(());
+      }
+    };
+
+    let module_id =
+      self.handle_source(file_path, module_name, file_content, profile)?;
+
+    self.graph.nodes[parent_id].children.push(module_id);
+
+    Ok(())
+  }
+
+  /// Parse source code, adding a module node for it along with its items.
+  fn handle_source(
+    &mut self,
+    file_path: &Path,
+    module_name: String,
+    file_content: String,
+    profile: &mut PackageProfile,
+  ) -> Result<NodeId> {
+    let parse = Instant::now();
+
+    self.declaration_order = 0;
+
+    let module_id = self.graph.nodes.len();
+
+    let module_node = Node {
+      id: module_id,
+      name: module_name,
+      kind: NodeKind::Module {
+        path: file_path.to_path_buf(),
+        macro_invocations: 0,
+        is_facade: false,
+        external_crates: Vec::new(),
+        is_skipped: false,
+      },
+      line: Some(1),
+      changed: self.changes.contains_key(file_path),
+      source_code: file_content,
+      ..Default::default()
+    };
+
+    self.graph.nodes.push(module_node);
+
+    match parse_file(&self.graph.nodes[module_id].source_code) {
+      Ok(syntax) => {
+        profile.parse += parse.elapsed();
+
+        self.graph.nodes[module_id].doc_hidden =
+          Self::is_doc_hidden(&syntax.attrs);
+
+        if self.is_no_std(&syntax.attrs) {
+          if let NodeKind::Package { no_std, .. } =
+            &mut self.graph.nodes[profile.id].kind
+          {
+            *no_std = true;
+          }
+        }
+
+        let items = Instant::now();
+        self.handle_syntactic_items(&syntax.items, file_path, module_id)?;
+        profile.items += items.elapsed();
+      }
+      Err(error) => {
+        profile.parse += parse.elapsed();
+        self.warn(WarningCategory::Parse, error, file_path);
+      }
+    }
+
+    Ok(module_id)
+  }
+
+  /// Every Rust source file under `dir`, honoring ignore rules unless
+  /// `no_ignore` is set.
+  fn walk(&mut self, dir: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(dir)
+      .hidden(false)
+      .parents(!self.config.no_ignore)
+      .ignore(!self.config.no_ignore)
+      .git_ignore(!self.config.no_ignore)
+      .git_global(!self.config.no_ignore)
+      .git_exclude(!self.config.no_ignore)
+      .require_git(false)
+      .build()
+      .filter_map(|entry| {
+        entry
+          .map_err(|error| {
+            self.warn(WarningCategory::Walk, error, dir);
+          })
+          .ok()
+      })
+      .filter(|entry| {
+        entry
+          .file_type()
+          .is_some_and(|file_type| file_type.is_file())
+          && entry.path().extension().is_some_and(|ext| ext == "rs")
+      })
+      .map(|entry| entry.into_path())
+      .collect()
+  }
+
+  /// Drop the files of modules whose `mod` declarations in other files are
+  /// disabled by `cfg` attributes, along with those of their submodules, which
+  /// a walk of the source directory finds regardless.
+  fn without_disabled_modules(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+    if self.config.cfg.is_empty() {
+      return files;
+    }
+
+    let mut disabled = Vec::new();
+
+    for file_path in &files {
+      let Ok(file) = fs::read_to_string(file_path)
+        .map_err(Error::from)
+        .and_then(|source| Ok(parse_file(&source)?))
+      else {
+        continue;
+      };
+
+      let Some(parent) = file_path.parent() else {
+        continue;
+      };
+
+      let dir = match file_path.file_name().and_then(|name| name.to_str()) {
+        Some("mod.rs" | "lib.rs" | "main.rs") => parent.to_path_buf(),
+        _ => parent.join(file_path.file_stem().unwrap_or_default()),
+      };
+
+      self.disabled_modules(&file.items, file_path, &dir, &mut disabled);
+    }
+
+    files
+      .into_iter()
+      .filter(|file_path| {
+        !disabled
+          .iter()
+          .any(|(file, dir)| file_path == file || file_path.starts_with(dir))
+      })
+      .collect()
+  }
+
+  /// The file and directory of each out-of-line module among `items`, or
+  /// inside their inline modules, whose declaration is disabled by a `cfg`
+  /// attribute.
+  fn disabled_modules(
+    &self,
+    items: &[Item],
+    file_path: &Path,
+    dir: &Path,
+    disabled: &mut Vec<(PathBuf, PathBuf)>,
+  ) {
+    for item in items {
+      let Item::Mod(item) = item else {
+        continue;
+      };
+
+      let name = item.ident.to_string();
+
+      match &item.content {
+        Some((_, items)) if self.is_enabled(&item.attrs) => {
+          self.disabled_modules(items, file_path, &dir.join(&name), disabled);
+        }
+        Some(_) => {}
+        None if self.is_enabled(&item.attrs) => {}
+        None => match Self::path_attribute(&item.attrs) {
+          Some(path) => {
+            let file = file_path.parent().unwrap_or(dir).join(path);
+            let dir = file.with_extension("");
+            disabled.push((file, dir));
+          }
+          None => {
+            disabled.push((dir.join(format!("{name}.rs")), dir.join(&name)));
+          }
+        },
+      }
+    }
+  }
+
+  /// A module's name: its file's path relative to `dir`, or failing that, to
+  /// the package.
+  fn module_name(
+    &mut self,
+    file_path: &Path,
+    dir: &Path,
+    package_path: &Path,
+  ) -> String {
+    match file_path
+      .strip_prefix(dir)
+      .or_else(|_| file_path.strip_prefix(package_path))
+    {
+      Ok(relative_path) => relative_path.to_string_lossy().into_owned(),
+      Err(error) => {
+        self.warn(WarningCategory::Path, error, file_path);
+        file_path.to_string_lossy().into_owned()
+      }
+    }
+  }
+
+  /// Queue the file an out-of-line `mod` declaration refers to, found either
+  /// through a `#[path]` attribute or next to the declaring module.
+  fn declare_module(&mut self, item: &ItemMod, file_path: &Path) {
+    let Some(module_dir) = self.module_dir.clone() else {
+      return;
+    };
+
+    let name = item.ident.to_string();
+
+    let (file, dir) = match Self::path_attribute(&item.attrs) {
+      Some(path) => {
+        let file = file_path.parent().unwrap_or(&module_dir).join(path);
+        let dir = file.parent().unwrap_or(&module_dir).to_path_buf();
+        (file, dir)
+      }
+      None => {
+        let dir = module_dir.join(&name);
+
+        let file = module_dir.join(format!("{name}.rs"));
+
+        if file.is_file() {
+          (file, dir)
+        } else {
+          (dir.join("mod.rs"), dir)
+        }
+      }
+    };
+
+    if !file.is_file() {
+      self.warn(
+        WarningCategory::Read,
+        format!("no file found for module `{name}`"),
+        file_path,
+      );
+      return;
+    }
+
+    self.declared_modules.push((file, dir));
+  }
+
+  fn warn(
+    &mut self,
+    category: WarningCategory,
+    message: impl Display,
+    path: &Path,
+  ) {
+    self.graph.warnings.push(Warning {
+      category,
+      message: message.to_string(),
+      path: path.to_path_buf(),
+    });
+  }
+
+  fn handle_syntactic_items(
+    &mut self,
+    items: &[Item],
+    file_path: &Path,
+    parent_id: NodeId,
+  ) -> Result {
+    for item in items {
+      self.check_deadline()?;
+
+      if !self.is_enabled(Self::attributes(item)) {
+        continue;
+      }
+
+      // Inline modules' items count toward the inline module.
+      if !matches!(item, Item::Mod(_)) {
+        let crates = ExternalCrateVisitor::visit(item, &self.dependencies);
+
+        if let NodeKind::Module {
+          external_crates, ..
+        } = &mut self.graph.nodes[parent_id].kind
+        {
+          for name in crates {
+            if !external_crates.contains(&name) {
+              external_crates.push(name);
+            }
+          }
+
+          external_crates.sort();
+        }
+      }
+
+      let source_code = item.to_token_stream().to_string();
+
+      // tracing::info!("Processing item: {}", source_code);
+
+      let node_id = self.graph.nodes.len();
+
+      let documentation = Self::documentation(Self::attributes(item));
+
+      let mut node = Node {
+        id: node_id,
+        line: Some(Self::line(item)),
+        aliases: Self::aliases(item),
+        doc_hidden: Self::is_doc_hidden(Self::attributes(item)),
+        lifetimes: Self::lifetimes(item),
+        where_clause: Self::where_clause(Self::generics(item)),
+        doc_examples: Self::doc_examples(&documentation),
+        documentation,
+        changed: self.is_changed(
+          file_path,
+          item.span().start().line,
+          item.span().end().line,
+        ),
+        transformed_by: Self::attribute_macros(item),
+        source_code,
+        ..Default::default()
+      };
+
+      match item {
+        Item::Const(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.kind = NodeKind::Const {
+            ty: render::ty(&i.ty),
+            value: i.expr.to_token_stream().to_string(),
+          };
+        }
+        Item::Enum(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds = self.handle_bounds(node_id, parent_id, &i.generics, []);
+          let variants = i
+            .variants
+            .iter()
+            .filter(|variant| self.is_enabled(&variant.attrs))
+            .map(|variant| variant.ident.to_string())
+            .collect::<Vec<String>>();
+          node.member_count = Some(variants.len());
+          let derives = self.handle_derives(node_id, &i.attrs);
+
+          node.kind = NodeKind::Enum {
+            variants,
+            marker_traits: Self::marker_traits(&derives),
+            derives,
+            non_exhaustive: i
+              .attrs
+              .iter()
+              .any(|attr| attr.path.is_ident("non_exhaustive")),
+            self_referential: false,
+          };
+        }
+        Item::Fn(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.sig.ident.to_string();
+          node.bounds =
+            self.handle_bounds(node_id, parent_id, &i.sig.generics, []);
+
+          if let Some(name) = Self::proc_macro_derive(&i.attrs) {
+            self.derive_macros.insert(name, node_id);
+          }
+
+          node.kind = Self::function(&i.sig, Some(&i.block), &i.attrs);
+        }
+        Item::Macro(i) => {
+          node.name = i
+            .ident
+            .as_ref()
+            .map_or("macro".to_string(), |ident| ident.to_string());
+
+          let macro_rules = i.mac.path.is_ident("macro_rules");
+
+          let mut rule_count = 0;
+
+          if macro_rules {
+            let rules = MacroRules::parse(i.mac.tokens.clone());
+
+            rule_count = rules.len().try_into().unwrap_or(u32::MAX);
+
+            if self.config.expand_macros {
+              self.macros.insert(node.name.clone(), rules);
+            }
+          } else if i.ident.is_none() {
+            if let NodeKind::Module {
+              macro_invocations, ..
+            } = &mut self.graph.nodes[parent_id].kind
+            {
+              *macro_invocations += 1;
+            }
+
+            if self.config.expand_macros {
+              self.invocations.push((
+                node_id,
+                i.mac.path.segments.last().unwrap().ident.to_string(),
+                i.mac.tokens.clone(),
+                file_path.to_path_buf(),
+              ));
+            }
+          }
+
+          node.kind = NodeKind::Macro {
+            macro_rules,
+            rule_count,
+          };
+        }
+        Item::Macro2(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.kind = NodeKind::Macro {
+            macro_rules: false,
+            rule_count: 0,
+          };
+        }
+        Item::Mod(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.kind = NodeKind::Module {
+            path: file_path.to_path_buf(),
+            macro_invocations: 0,
+            is_facade: false,
+            external_crates: Vec::new(),
+            is_skipped: false,
+          };
+
+          if i.content.is_none() {
+            self.declarations.insert(node_id);
+          }
+        }
+        Item::Static(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.kind = NodeKind::Static {
+            ty: render::ty(&i.ty),
+            mutability: i.mutability.is_some(),
+          };
+        }
+        Item::Struct(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds = self.handle_bounds(node_id, parent_id, &i.generics, []);
+          let fields = self.handle_fields(&i.fields);
+          node.member_count = Some(fields.len());
+          let derives = self.handle_derives(node_id, &i.attrs);
+
+          node.kind = NodeKind::Struct {
+            fields,
+            marker_traits: Self::marker_traits(&derives),
+            derives,
+            non_exhaustive: i
+              .attrs
+              .iter()
+              .any(|attr| attr.path.is_ident("non_exhaustive")),
+            self_referential: false,
+          };
+        }
+        Item::Union(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds = self.handle_bounds(node_id, parent_id, &i.generics, []);
+          let fields = self.handle_fields(&i.fields.named);
+          node.member_count = Some(fields.len());
+          node.kind = NodeKind::Union { fields };
+        }
+        Item::Trait(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds =
+            self.handle_bounds(node_id, parent_id, &i.generics, &i.supertraits);
+          node.member_count = Some(
+            i.items
+              .iter()
+              .filter(|item| self.is_enabled(Self::trait_item_attributes(item)))
+              .count(),
+          );
+          node.kind = NodeKind::Trait {
+            is_auto: i.auto_token.is_some(),
+            is_unsafe: i.unsafety.is_some(),
+            object_safe: Self::object_safety(i),
+            external_implementors: Vec::new(),
+            provided_methods: i
+              .items
+              .iter()
+              .filter_map(|item| match item {
+                TraitItem::Method(method)
+                  if method.default.is_some()
+                    && self.is_enabled(&method.attrs) =>
+                {
+                  Some(method.sig.ident.to_string())
+                }
+                _ => None,
+              })
+              .collect(),
+          };
+        }
+        Item::TraitAlias(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds =
+            self.handle_bounds(node_id, parent_id, &i.generics, &i.bounds);
+          node.kind = NodeKind::TraitAlias {
+            generics: render::generics(&i.generics),
+          };
+        }
+        Item::Type(i) => {
+          node.visibility = (&i.vis).into();
+          node.name = i.ident.to_string();
+          node.bounds = self.handle_bounds(node_id, parent_id, &i.generics, []);
+          node.kind = NodeKind::Type {
+            generics: render::generics(&i.generics),
+          };
+        }
+        Item::Impl(i) => {
+          self.handle_impl(i, node_id, parent_id);
+
+          let self_type = render::ty(&i.self_ty);
+
+          let trait_name = i.trait_.as_ref().map(|(bang, path, _)| {
+            format!(
+              "{}{}",
+              if bang.is_some() { "!" } else { "" },
+              render::path(path)
+            )
+          });
+
+          node.name = match &trait_name {
+            Some(trait_name) => format!("impl {trait_name} for {self_type}"),
+            None => format!("impl {self_type}"),
+          };
+
+          node.member_count = Some(
+            i.items
+              .iter()
+              .filter(|item| self.is_enabled(Self::impl_item_attributes(item)))
+              .count(),
+          );
+
+          node.kind = NodeKind::Impl {
+            self_type,
+            trait_name,
+            is_unsafe: i.unsafety.is_some(),
+          };
+        }
+        Item::Use(i) => {
+          self.imports.push((parent_id, Import::flatten(i)));
+          continue;
+        }
+        Item::Verbatim(tokens) => {
+          node.visibility = (&Self::verbatim_visibility(tokens)).into();
+          node.name = Self::verbatim_name(tokens);
+          node.kind = NodeKind::Verbatim {
+            tokens: tokens.to_string(),
+          };
+
+          self.warn(
+            WarningCategory::Verbatim,
+            format!(
+              "kept `{}` on line {} as unparsed tokens",
+              node.name,
+              node.line.unwrap_or_default()
+            ),
+            file_path,
+          );
+        }
+        _ => continue,
+      }
+
+      node.churn = self.churn(&node.kind, file_path, item.span());
+
+      node.declaration_order = self.declaration_order;
+      self.declaration_order += 1;
+
+      self.graph.nodes.push(node);
+      self.graph.nodes[parent_id].children.push(node_id);
+
+      if let Item::Trait(item_trait) = item {
+        self.handle_trait_items(item_trait, node_id, parent_id, file_path);
+      }
+
+      if let Item::Impl(item_impl) = item {
+        self.handle_impl_items(item_impl, node_id, parent_id, file_path);
+      }
+
+      if let Item::Mod(item_mod) = item {
+        match &item_mod.content {
+          Some((_, items)) => {
+            let module_dir = self.module_dir.clone();
+
+            self.module_dir = module_dir
+              .as_ref()
+              .map(|dir| dir.join(item_mod.ident.to_string()));
+
+            self.handle_syntactic_items(items, file_path, node_id)?;
+
+            self.module_dir = module_dir;
+          }
+          None => self.declare_module(item_mod, file_path),
+        }
+      }
+
+      if !self.config.no_deps_edges {
+        self.traces.push((item.clone(), node_id, parent_id));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Add a trait's associated types and methods as its children, keeping
+  /// the generics of generic associated types and the trait bounds they're
+  /// declared with, such as `Display` in `type Output: Display;`. Methods
+  /// without a default body count as having an empty one.
+  fn handle_trait_items(
+    &mut self,
+    item: &ItemTrait,
+    trait_id: NodeId,
+    module_id: NodeId,
+    file_path: &Path,
+  ) {
+    for trait_item in &item.items {
+      let (attrs, name, generics, line) = match trait_item {
+        TraitItem::Type(i) => (
+          &i.attrs,
+          &i.ident,
+          &i.generics,
+          i.type_token.span().start().line,
+        ),
+        TraitItem::Method(i) => (
+          &i.attrs,
+          &i.sig.ident,
+          &i.sig.generics,
+          i.sig.fn_token.span().start().line,
+        ),
+        _ => continue,
+      };
+
+      if !self.is_enabled(attrs) {
+        continue;
+      }
+
+      let node_id = self.graph.nodes.len();
+
+      let documentation = Self::documentation(attrs);
+
+      let mut node = Node {
+        id: node_id,
+        name: name.to_string(),
+        lifetimes: generics
+          .lifetimes()
+          .map(|param| param.lifetime.to_string())
+          .collect(),
+        visibility: self.graph.nodes[trait_id].visibility,
+        line: Some(line),
+        changed: self.is_changed(
+          file_path,
+          trait_item.span().start().line,
+          trait_item.span().end().line,
+        ),
+        source_code: trait_item.to_token_stream().to_string(),
+        doc_hidden: Self::is_doc_hidden(attrs),
+        where_clause: Self::where_clause(Some(generics)),
+        doc_examples: Self::doc_examples(&documentation),
+        documentation,
+        declaration_order: self.declaration_order,
+        ..Default::default()
+      };
+
+      match trait_item {
+        TraitItem::Type(i) => {
+          node.kind = NodeKind::Type {
+            generics: render::generics(&i.generics),
+          };
+          node.bounds =
+            self.handle_bounds(node_id, module_id, &i.generics, &i.bounds);
+        }
+        TraitItem::Method(i) => {
+          node.kind = Self::function(&i.sig, i.default.as_ref(), &i.attrs);
+          node.bounds =
+            self.handle_bounds(node_id, module_id, &i.sig.generics, []);
+        }
+        _ => unreachable!(),
+      }
+
+      node.churn = self.churn(&node.kind, file_path, trait_item.span());
+
+      self.declaration_order += 1;
+
+      self.graph.nodes.push(node);
+      self.graph.nodes[trait_id].children.push(node_id);
+    }
+  }
+
+  /// Add an impl block's methods and associated consts as its children,
+  /// which the dependencies of their bodies are attributed to when the impl
+  /// is traced.
+  fn handle_impl_items(
+    &mut self,
+    item: &ItemImpl,
+    impl_id: NodeId,
+    module_id: NodeId,
+    file_path: &Path,
+  ) {
+    for impl_item in &item.items {
+      let (attrs, vis, name, generics, line) = match impl_item {
+        ImplItem::Const(i) => (
+          &i.attrs,
+          &i.vis,
+          &i.ident,
+          None,
+          i.const_token.span().start().line,
+        ),
+        ImplItem::Method(i) => (
+          &i.attrs,
+          &i.vis,
+          &i.sig.ident,
+          Some(&i.sig.generics),
+          i.sig.fn_token.span().start().line,
+        ),
+        _ => continue,
+      };
+
+      if !self.is_enabled(attrs) {
+        continue;
+      }
+
+      let node_id = self.graph.nodes.len();
+
+      let documentation = Self::documentation(attrs);
+
+      let mut node = Node {
+        id: node_id,
+        name: name.to_string(),
+        visibility: vis.into(),
+        line: Some(line),
+        changed: self.is_changed(
+          file_path,
+          impl_item.span().start().line,
+          impl_item.span().end().line,
+        ),
+        lifetimes: generics.map_or_else(Vec::new, |generics| {
+          generics
+            .lifetimes()
+            .map(|param| param.lifetime.to_string())
+            .collect()
+        }),
+        where_clause: Self::where_clause(generics),
+        source_code: impl_item.to_token_stream().to_string(),
+        doc_hidden: Self::is_doc_hidden(attrs),
+        doc_examples: Self::doc_examples(&documentation),
+        documentation,
+        declaration_order: self.declaration_order,
+        ..Default::default()
+      };
+
+      match impl_item {
+        ImplItem::Const(i) => {
+          node.kind = NodeKind::Const {
+            ty: render::ty(&i.ty),
+            value: i.expr.to_token_stream().to_string(),
+          };
+        }
+        ImplItem::Method(i) => {
+          node.bounds =
+            self.handle_bounds(node_id, module_id, &i.sig.generics, []);
+          node.kind = Self::function(&i.sig, Some(&i.block), &i.attrs);
+        }
+        _ => unreachable!(),
+      }
+
+      node.churn = self.churn(&node.kind, file_path, impl_item.span());
+
+      self.declaration_order += 1;
+
+      self.graph.nodes.push(node);
+      self.graph.nodes[impl_id].children.push(node_id);
+    }
+  }
+
+  /// The number of commits that touched the lines `span` covers in
+  /// `file_path`, for functions and type definitions when
+  /// [`Config::git_churn`] is set.
+  fn churn(
+    &self,
+    kind: &NodeKind,
+    file_path: &Path,
+    span: Span,
+  ) -> Option<u32> {
+    if !self.track_churn
+      || !matches!(
+        kind,
+        NodeKind::Function { .. }
+          | NodeKind::Struct { .. }
+          | NodeKind::Enum { .. }
+          | NodeKind::Union { .. }
+          | NodeKind::Trait { .. }
+          | NodeKind::Type { .. }
+      )
+    {
+      return None;
+    }
+
+    git::churn(file_path, span.start().line, span.end().line).ok()
+  }
+
+  /// The kind of a function or method with the given signature, body, if
+  /// it has one, and attributes.
+  fn function(
+    sig: &Signature,
+    block: Option<&Block>,
+    attrs: &[Attribute],
+  ) -> NodeKind {
+    let body = block.map(BodyVisitor::visit).unwrap_or_default();
+
+    NodeKind::Function {
+      arguments: sig
+        .inputs
+        .iter()
+        .filter_map(|arg| {
+          if let FnArg::Typed(pat_type) = arg {
+            Some(Field {
+              name: pat_type.pat.to_token_stream().to_string(),
+              type_name: render::ty(&pat_type.ty),
+            })
+          } else {
+            None
+          }
+        })
+        .collect(),
+      return_type: match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(render::ty(ty)),
+      },
+      unsafe_block_count: body.unsafe_blocks,
+      panic_count: body.panics,
+      todo_count: body.todo_count,
+      unimplemented_count: body.unimplemented,
+      is_recursive: false,
+      role: FunctionRole::from_attributes(attrs),
+    }
+  }
+
+  /// Expand item-position invocations of in-crate `macro_rules!` macros,
+  /// adding the items they define as children of the invocation. Expansions
+  /// that invoke further macros are expanded in turn, up to a fixed depth.
+  fn expand_macros(&mut self) -> Result {
+    for _ in 0..MACRO_EXPANSION_DEPTH {
+      let invocations = take(&mut self.invocations);
+
+      if invocations.is_empty() {
+        break;
+      }
+
+      for (node_id, name, tokens, file_path) in invocations {
+        self.check_deadline()?;
+
+        let Some(expansion) = self
+          .macros
+          .get(&name)
+          .and_then(|rules| rules.expand(&tokens))
+        else {
+          continue;
+        };
+
+        match syn::parse2::<syn::File>(expansion) {
+          Ok(file) => {
+            self.handle_syntactic_items(&file.items, &file_path, node_id)?
+          }
+          Err(error) => self.warn(
+            WarningCategory::Parse,
+            format!("expansion of `{name}!`: {error}"),
+            &file_path,
+          ),
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Whether the `cfg` attributes in `attrs` hold for the configured cfg
+  /// options. Everything is enabled if no options are configured.
+  fn is_enabled(&self, attrs: &[Attribute]) -> bool {
+    if self.config.cfg.is_empty() {
+      return true;
+    }
+
+    attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("cfg"))
+      .all(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) if list.nested.len() == 1 => {
+          Cfg::evaluate(&self.config.cfg, &list.nested[0])
+        }
+        _ => true,
+      })
+  }
+
+  /// Whether a crate root's inner attributes include `no_std`, either
+  /// directly or through a `cfg_attr` whose predicate holds.
+  fn is_no_std(&self, attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+      if attr.path.is_ident("no_std") {
+        return true;
+      }
+
+      if !attr.path.is_ident("cfg_attr") {
+        return false;
+      }
+
+      let Ok(Meta::List(list)) = attr.parse_meta() else {
+        return false;
+      };
+
+      let mut nested = list.nested.iter();
+
+      let Some(predicate) = nested.next() else {
+        return false;
+      };
+
+      nested.any(|nested| {
+        matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("no_std"))
+      }) && (self.config.cfg.is_empty()
+        || Cfg::evaluate(&self.config.cfg, predicate))
+    })
+  }
+
+  /// The attributes written on an item.
+  fn attributes(item: &Item) -> &[Attribute] {
+    match item {
+      Item::Const(i) => &i.attrs,
+      Item::Enum(i) => &i.attrs,
+      Item::ExternCrate(i) => &i.attrs,
+      Item::Fn(i) => &i.attrs,
+      Item::ForeignMod(i) => &i.attrs,
+      Item::Impl(i) => &i.attrs,
+      Item::Macro(i) => &i.attrs,
+      Item::Macro2(i) => &i.attrs,
+      Item::Mod(i) => &i.attrs,
+      Item::Static(i) => &i.attrs,
+      Item::Struct(i) => &i.attrs,
+      Item::Trait(i) => &i.attrs,
+      Item::TraitAlias(i) => &i.attrs,
+      Item::Type(i) => &i.attrs,
+      Item::Union(i) => &i.attrs,
+      Item::Use(i) => &i.attrs,
+      _ => &[],
+    }
+  }
+
+  /// The path given by a `mod` declaration's `#[path]` attribute.
+  fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+    attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("path"))
+      .find_map(|attr| match attr.parse_meta() {
+        Ok(Meta::NameValue(MetaNameValue {
+          lit: Lit::Str(path),
+          ..
+        })) => Some(path.value()),
+        _ => None,
+      })
+  }
+
+  /// The attributes written on a trait item.
+  fn trait_item_attributes(item: &TraitItem) -> &[Attribute] {
+    match item {
+      TraitItem::Const(i) => &i.attrs,
+      TraitItem::Macro(i) => &i.attrs,
+      TraitItem::Method(i) => &i.attrs,
+      TraitItem::Type(i) => &i.attrs,
+      _ => &[],
+    }
+  }
+
+  /// The attributes written on an impl item.
+  fn impl_item_attributes(item: &ImplItem) -> &[Attribute] {
+    match item {
+      ImplItem::Const(i) => &i.attrs,
+      ImplItem::Macro(i) => &i.attrs,
+      ImplItem::Method(i) => &i.attrs,
+      ImplItem::Type(i) => &i.attrs,
+      _ => &[],
+    }
+  }
+
+  /// The text of doc comments and `#[doc = "..."]` attributes, one per line,
+  /// without the space conventionally following `///`.
+  fn documentation(attrs: &[Attribute]) -> String {
+    attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("doc"))
+      .filter_map(|attr| match attr.parse_meta() {
+        Ok(Meta::NameValue(MetaNameValue {
+          lit: Lit::Str(text),
+          ..
+        })) => Some(text.value()),
+        _ => None,
+      })
+      .map(|text| text.strip_prefix(' ').map(str::to_owned).unwrap_or(text))
+      .collect::<Vec<String>>()
+      .join("\n")
+  }
+
+  /// The contents of fenced code blocks in `documentation` that rustdoc
+  /// would treat as Rust, those fenced with ```` ``` ```` or ```` ```rust ````
+  /// or with only rustdoc attributes like `no_run`. Hidden lines starting
+  /// with `# ` are kept as they are.
+  fn doc_examples(documentation: &str) -> Vec<String> {
+    const ATTRIBUTES: &[&str] = &[
+      "compile_fail",
+      "edition2015",
+      "edition2018",
+      "edition2021",
+      "ignore",
+      "no_run",
+      "should_panic",
+      "test_harness",
+    ];
+
+    let mut examples = Vec::new();
+
+    let mut example = None::<(String, bool)>;
+
+    for line in documentation.lines() {
+      let fence = line.trim_start().strip_prefix("```");
+
+      match (&mut example, fence) {
+        (None, Some(info)) => {
+          let tokens = info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<&str>>();
+
+          let is_rust = tokens.contains(&"rust")
+            || tokens.iter().all(|token| ATTRIBUTES.contains(token));
+
+          example = Some((String::new(), is_rust));
+        }
+        (Some(_), Some(_)) => {
+          if let Some((code, true)) = example.take() {
+            examples.push(code);
+          }
+        }
+        (Some((code, _)), None) => {
+          code.push_str(line);
+          code.push('\n');
+        }
+        (None, None) => {}
+      }
+    }
+
+    examples
+  }
+
+  /// The lifetime parameters of a generic item, such as `'a`, in
+  /// declaration order.
+  fn lifetimes(item: &Item) -> Vec<String> {
+    Self::generics(item).map_or_else(Vec::new, |generics| {
+      generics
+        .lifetimes()
+        .map(|param| param.lifetime.to_string())
+        .collect()
+    })
+  }
+
+  /// The predicates of a generic item's `where` clause.
+  fn where_clause(generics: Option<&Generics>) -> Vec<Predicate> {
+    generics
+      .and_then(|generics| generics.where_clause.as_ref())
+      .map_or_else(Vec::new, |where_clause| {
+        where_clause.predicates.iter().map(Predicate::new).collect()
+      })
+  }
+
+  /// The generic parameters of an item that can have them.
+  fn generics(item: &Item) -> Option<&Generics> {
+    match item {
+      Item::Enum(i) => Some(&i.generics),
+      Item::Fn(i) => Some(&i.sig.generics),
+      Item::Struct(i) => Some(&i.generics),
+      Item::Trait(i) => Some(&i.generics),
+      Item::TraitAlias(i) => Some(&i.generics),
+      Item::Type(i) => Some(&i.generics),
+      Item::Union(i) => Some(&i.generics),
+      _ => None,
+    }
+  }
+
+  /// The visibility a verbatim item starts with, if any.
+  fn verbatim_visibility(tokens: &TokenStream) -> syn::Visibility {
+    let parser = |input: ParseStream| {
+      let visibility = input.parse()?;
+      input.parse::<TokenStream>()?;
+      Ok(visibility)
+    };
+
+    parser
+      .parse2(tokens.clone())
+      .unwrap_or(syn::Visibility::Inherited)
+  }
+
+  /// The name of a verbatim item, taken to be its first identifier that isn't
+  /// a keyword, such as `foo` in `pub macro foo() {}`, or "verbatim" if it
+  /// has none outside of delimiters.
+  fn verbatim_name(tokens: &TokenStream) -> String {
+    tokens
+      .clone()
+      .into_iter()
+      .find_map(|token| match token {
+        TokenTree::Ident(ident) => parse_str::<Ident>(&ident.to_string()).ok(),
+        _ => None,
+      })
+      .map_or_else(|| "verbatim".into(), |ident| ident.to_string())
+  }
+
+  /// Whether the attributes include `#[doc(hidden)]`, which rustdoc honors by
+  /// leaving the item, and anything inside it, out of the documentation.
+  fn is_doc_hidden(attrs: &[Attribute]) -> bool {
+    attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("doc"))
+      .filter_map(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => Some(list.nested),
+        _ => None,
+      })
+      .flatten()
+      .any(|nested| {
+        matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hidden"))
+      })
+  }
+
+  /// Search aliases from `#[doc(alias = "...")]` and
+  /// `#[doc(alias("...", ...))]` attributes.
+  fn aliases(item: &Item) -> Vec<String> {
+    Self::attributes(item)
+      .iter()
+      .filter(|attr| attr.path.is_ident("doc"))
+      .filter_map(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => Some(list.nested),
+        _ => None,
+      })
+      .flatten()
+      .flat_map(|nested| match nested {
+        NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+          path,
+          lit: Lit::Str(alias),
+          ..
+        }))
+          if path.is_ident("alias") =>
+        {
+          vec![alias.value()]
+        }
+        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("alias") => {
+          list
+            .nested
+            .into_iter()
+            .filter_map(|nested| match nested {
+              NestedMeta::Lit(Lit::Str(alias)) => Some(alias.value()),
+              _ => None,
+            })
+            .collect()
+        }
+        _ => Vec::new(),
+      })
+      .collect()
+  }
+
+  /// Whether a trait can be used as `dyn Trait`, judged from its own items:
+  /// it mustn't require `Self: Sized` or have associated consts or generic
+  /// associated types, and each method not bounded by `Self: Sized` must take
+  /// a receiver, have no type parameters, not be `async` or return `impl
+  /// Trait`, and mention `Self` only through the receiver or associated
+  /// types like `Self::Item`. Supertraits' own object safety isn't checked,
+  /// since they may be defined elsewhere, and traits with macro invocations
+  /// among their items are undeterminable.
+  fn object_safety(item: &ItemTrait) -> Option<bool> {
+    let is_sized = |bound: &TypeParamBound| match bound {
+      TypeParamBound::Trait(bound) => bound.path.is_ident("Sized"),
+      TypeParamBound::Lifetime(_) => false,
+    };
+
+    if item.supertraits.iter().any(is_sized) {
+      return Some(false);
+    }
+
+    for trait_item in &item.items {
+      match trait_item {
+        TraitItem::Const(_) => return Some(false),
+        TraitItem::Type(ty) if !ty.generics.params.is_empty() => {
+          return Some(false)
+        }
+        TraitItem::Method(method) => {
+          let sig = &method.sig;
+
+          let requires_sized =
+            sig.generics.where_clause.iter().any(|where_clause| {
+              where_clause.predicates.iter().any(|predicate| {
+                let WherePredicate::Type(predicate) = predicate else {
+                  return false;
+                };
+
+                predicate.bounded_ty.to_token_stream().to_string() == "Self"
+                  && predicate.bounds.iter().any(is_sized)
+              })
+            });
+
+          if requires_sized {
+            continue;
+          }
+
+          let has_receiver =
+            sig.inputs.first().is_some_and(|input| match input {
+              FnArg::Receiver(_) => true,
+              FnArg::Typed(pat_type) => {
+                pat_type.pat.to_token_stream().to_string() == "self"
+              }
+            });
+
+          let has_type_parameters = sig
+            .generics
+            .params
+            .iter()
+            .any(|param| !matches!(param, GenericParam::Lifetime(_)));
+
+          let returns_impl_trait = match &sig.output {
+            ReturnType::Type(_, ty) => {
+              ty.to_token_stream().into_iter().any(|token| match token {
+                TokenTree::Ident(ident) => ident == "impl",
+                _ => false,
+              })
+            }
+            ReturnType::Default => false,
+          };
+
+          let mentions_self = sig
+            .inputs
+            .iter()
+            .skip(1)
+            .map(|input| input.to_token_stream())
+            .chain(match &sig.output {
+              ReturnType::Type(_, ty) => Some(ty.to_token_stream()),
+              ReturnType::Default => None,
+            })
+            .any(Self::mentions_self);
+
+          if !has_receiver
+            || has_type_parameters
+            || sig.asyncness.is_some()
+            || returns_impl_trait
+            || mentions_self
+          {
+            return Some(false);
+          }
+        }
+        TraitItem::Macro(_) | TraitItem::Verbatim(_) => return None,
+        _ => {}
+      }
+    }
+
+    Some(true)
+  }
+
+  /// Whether `tokens` name `Self` other than in a path like `Self::Item`.
+  fn mentions_self(tokens: TokenStream) -> bool {
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+      let mentions = match token {
+        TokenTree::Ident(ident) => {
+          ident == "Self"
+            && !matches!(
+              tokens.peek(),
+              Some(TokenTree::Punct(punct)) if punct.as_char() == ':'
+            )
+        }
+        TokenTree::Group(group) => Self::mentions_self(group.stream()),
+        _ => false,
+      };
+
+      if mentions {
+        return true;
+      }
+    }
+
+    false
+  }
+
+  /// The paths of an item's attributes that are likely attribute macros:
+  /// those that aren't built in or belong to a tool. Single-segment
+  /// attributes on an item with a `#[derive]` are assumed to be derive
+  /// helpers, such as `#[serde(...)]`.
+  fn attribute_macros(item: &Item) -> Vec<String> {
+    let attrs = Self::attributes(item);
+
+    let has_derive = attrs.iter().any(|attr| attr.path.is_ident("derive"));
+
+    attrs
+      .iter()
+      .filter(|attr| match attr.path.get_ident() {
+        Some(ident) => {
+          !has_derive
+            && !BUILTIN_ATTRIBUTES.contains(&ident.to_string().as_str())
+        }
+        None => !attr.path.segments.first().is_some_and(|segment| {
+          TOOL_ATTRIBUTES.contains(&segment.ident.to_string().as_str())
+        }),
+      })
+      .map(|attr| Self::path_name(&attr.path))
+      .collect()
+  }
+
+  /// Record an implementation, to be linked once every package has been
+  /// analyzed. Blanket implementations over a type parameter are skipped.
+  fn handle_impl(
+    &mut self,
+    item: &ItemImpl,
+    impl_id: NodeId,
+    module_id: NodeId,
+  ) {
+    let path = match &item.trait_ {
+      Some((None, path, _)) => Some(path),
+      None => None,
+      // Negative implementations, like `impl !Send for T {}`, opt out of a
+      // trait rather than implementing it.
+      Some((Some(_), _, _)) => return,
+    };
+
+    let ty = match &*item.self_ty {
+      syn::Type::Path(ty) => Self::path_name(&ty.path),
+      ty => ty.to_token_stream().to_string(),
+    };
+
+    let is_blanket = item.generics.params.iter().any(
+      |param| matches!(param, GenericParam::Type(param) if param.ident == ty),
+    );
+
+    if !is_blanket {
+      self.implementations.push((
+        impl_id,
+        module_id,
+        ty,
+        path.map(Self::path_name),
+      ));
+    }
+  }
+
+  /// The line an item starts on, after any attributes and doc comments.
+  fn line(item: &Item) -> usize {
+    let mut tokens = item.to_token_stream().into_iter().peekable();
+
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+      if punct.as_char() != '#' {
+        break;
+      }
+
+      tokens.next();
+      tokens.next();
+    }
+
+    tokens
+      .next()
+      .map_or_else(|| item.span(), |token| token.span())
+      .start()
+      .line
+  }
+
+  /// Whether any line from `start` to `end` in `file` has changed.
+  fn is_changed(&self, file: &Path, start: usize, end: usize) -> bool {
+    self.changes.get(file).is_some_and(|ranges| {
+      ranges
+        .iter()
+        .any(|&(first, last)| first <= end && start <= last)
+    })
+  }
+
+  fn check_deadline(&self) -> Result {
+    match (self.deadline, self.config.timeout) {
+      (Some(deadline), Some(timeout)) if Instant::now() > deadline => {
+        Err(TimedOut(timeout).into())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn trace_dependencies(
+    &mut self,
+    item: &Item,
+    current_id: NodeId,
+    current_module_id: NodeId,
+    impls: &HashMap<NodeId, Vec<NodeId>>,
+    paths: Option<&[Option<String>]>,
+  ) {
+    let self_type = match item {
+      Item::Impl(i) => self.self_type(i, current_module_id),
+      _ => None,
+    };
+
+    let mut visitor = DependencyVisitor::new(
+      &mut self.graph,
+      current_id,
+      current_module_id,
+      self_type,
+      impls,
+      paths,
+    );
+
+    match item {
+      Item::Const(i) => visitor.visit_item_const(i),
+      Item::Enum(i) => visitor.visit_item_enum(i),
+      Item::ExternCrate(i) => visitor.visit_item_extern_crate(i),
+      Item::Fn(i) => visitor.visit_item_fn(i),
+      Item::ForeignMod(i) => visitor.visit_item_foreign_mod(i),
+      Item::Impl(i) => visitor.visit_item_impl(i),
+      Item::Mod(i) => visitor.visit_item_mod(i),
+      Item::Static(i) => visitor.visit_item_static(i),
+      Item::Struct(i) => visitor.visit_item_struct(i),
+      Item::Trait(i) => visitor.visit_item_trait(i),
+      Item::TraitAlias(i) => visitor.visit_item_trait_alias(i),
+      Item::Type(i) => visitor.visit_item_type(i),
+      Item::Union(i) => visitor.visit_item_union(i),
+      Item::Use(i) => visitor.visit_item_use(i),
+      _ => {}
+    }
+  }
+
+  /// The in-crate type an impl block is for, found by the last segment of
+  /// its path, if it's a path.
+  fn self_type(&self, item: &ItemImpl, module_id: NodeId) -> Option<NodeId> {
+    match &*item.self_ty {
+      syn::Type::Path(ty) => ty.path.segments.last().and_then(|segment| {
+        self.find(module_id, &segment.ident.to_string(), Self::is_type)
+      }),
+      _ => None,
+    }
+  }
+
+  /// The impl blocks of each in-crate type, whose items paths like
+  /// `Type::item` and `Self::item` name.
+  fn impls(&self) -> HashMap<NodeId, Vec<NodeId>> {
+    let mut impls = HashMap::<NodeId, Vec<NodeId>>::new();
+
+    for (item, impl_id, module_id) in &self.traces {
+      if let Item::Impl(item) = item {
+        if let Some(self_type) = self.self_type(item, *module_id) {
+          impls.entry(self_type).or_default().push(*impl_id);
+        }
+      }
+    }
+
+    impls
+  }
+
+  /// The fields of a struct or union that `cfg` attributes don't disable,
+  /// where those of a tuple struct are named by their position, like `0`.
+  fn handle_fields<'b>(
+    &self,
+    fields: impl IntoIterator<Item = &'b syn::Field>,
+  ) -> Vec<Field> {
+    fields
+      .into_iter()
+      .filter(|field| self.is_enabled(&field.attrs))
+      .enumerate()
+      .map(|(i, field)| Field {
+        name: field
+          .ident
+          .as_ref()
+          .map_or_else(|| i.to_string(), ToString::to_string),
+        type_name: render::ty(&field.ty),
+      })
+      .collect()
+  }
+
+  /// Record the traits named in trait bounds, both inline and in `where`
+  /// clauses, along with any `extra` bounds such as supertraits, to be linked
+  /// to in-crate traits once every package has been analyzed, and return the
+  /// trait bounds as rendered.
+  fn handle_bounds<'b>(
+    &mut self,
+    node_id: NodeId,
+    module_id: NodeId,
+    generics: &'b Generics,
+    extra: impl IntoIterator<Item = &'b TypeParamBound>,
+  ) -> Vec<String> {
+    let params = generics.params.iter().flat_map(|param| match param {
+      GenericParam::Type(param) => param.bounds.iter().collect(),
+      _ => Vec::new(),
+    });
+
+    let predicates = generics
+      .where_clause
+      .iter()
+      .flat_map(|where_clause| &where_clause.predicates)
+      .flat_map(|predicate| match predicate {
+        WherePredicate::Type(predicate) => predicate.bounds.iter().collect(),
+        _ => Vec::new(),
+      });
+
+    let mut names = Vec::new();
+
+    let mut bounds = Vec::new();
+
+    for bound in params.chain(predicates).chain(extra) {
+      if let TypeParamBound::Trait(trait_bound) = bound {
+        let name = Self::path_name(&trait_bound.path);
+
+        if !names.contains(&name) {
+          names.push(name);
+        }
+
+        let rendered = render::bounds([bound]);
+
+        if !bounds.contains(&rendered) {
+          bounds.push(rendered);
+        }
+      }
+    }
+
+    if !names.is_empty() {
+      self.bounds.push((node_id, module_id, names));
+    }
+
+    bounds
+  }
+
+  /// Render a path as its segment names joined by `::`, without generic
+  /// arguments.
+  pub(crate) fn path_name(path: &syn::Path) -> String {
+    path
+      .segments
+      .iter()
+      .map(|segment| segment.ident.to_string())
+      .collect::<Vec<_>>()
+      .join("::")
+  }
+
+  /// Record the paths named in `#[derive(...)]` attributes, to be linked to
+  /// in-crate derive macros once every package has been analyzed.
+  fn handle_derives(
+    &mut self,
+    node_id: NodeId,
+    attrs: &[Attribute],
+  ) -> Vec<String> {
+    let derives = attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("derive"))
+      .filter_map(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => Some(list.nested),
+        _ => None,
+      })
+      .flatten()
+      .filter_map(|nested| match nested {
+        NestedMeta::Meta(meta) => Some(Self::path_name(meta.path())),
+        NestedMeta::Lit(_) => None,
+      })
+      .collect::<Vec<_>>();
+
+    if !derives.is_empty() {
+      self.derives.push((node_id, derives.clone()));
+    }
+
+    derives
+  }
+
+  /// The marker traits among a type's derives.
+  fn marker_traits(derives: &[String]) -> Vec<String> {
+    derives
+      .iter()
+      .map(|derive| derive.rsplit("::").next().unwrap())
+      .filter(|name| MARKER_TRAITS.contains(name))
+      .map(str::to_owned)
+      .collect()
+  }
+
+  /// The name of the derive a `#[proc_macro_derive(Name)]` function defines.
+  fn proc_macro_derive(attrs: &[Attribute]) -> Option<String> {
+    attrs
+      .iter()
+      .filter(|attr| attr.path.is_ident("proc_macro_derive"))
+      .find_map(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => match list.nested.first() {
+          Some(NestedMeta::Meta(meta)) => {
+            meta.path().get_ident().map(|ident| ident.to_string())
+          }
+          _ => None,
+        },
+        _ => None,
+      })
+  }
+
+  fn resolve_derives(&mut self) {
+    for (node_id, derives) in take(&mut self.derives) {
+      for derive in derives {
+        let name = derive.rsplit("::").next().unwrap();
+
+        if let Some(&target) = self.derive_macros.get(name) {
+          self.graph.nodes[node_id].add_edge(target, EdgeKind::Derives);
+        }
+      }
+    }
+  }
+
+  /// Flag modules with a body that contain nothing but other modules and
+  /// `use` declarations, which aren't nodes, and no macro invocations that
+  /// might expand to items.
+  fn mark_facades(&mut self) {
+    let facades = self
+      .graph
+      .nodes
+      .iter()
+      .map(|node| {
+        !self.declarations.contains(&node.id)
+          && matches!(
+            node.kind,
+            NodeKind::Module {
+              macro_invocations: 0,
+              is_skipped: false,
+              ..
+            }
+          )
+          && node.children.iter().all(|&child| {
+            matches!(self.graph.nodes[child].kind, NodeKind::Module { .. })
+          })
+      })
+      .collect::<Vec<bool>>();
+
+    for (node, facade) in self.graph.nodes.iter_mut().zip(facades) {
+      if let NodeKind::Module { is_facade, .. } = &mut node.kind {
+        *is_facade = facade;
+      }
+    }
+  }
+
+  /// Move each re-exported item, other than modules, under the module of its
+  /// shortest public path, named as it's exported there, and renumber the
+  /// graph so that parents still come before their children.
+  fn collapse_reexports(&mut self) {
+    let paths = self.graph.paths();
+
+    let parents = self.graph.parents();
+
+    let mut ids = HashMap::<&str, Vec<NodeId>>::new();
+
+    for (id, path) in paths.iter().enumerate() {
+      if let Some(path) = path {
+        ids.entry(path).or_default().push(id);
+      }
+    }
+
+    let is_module =
+      |id: NodeId| matches!(self.graph.nodes[id].kind, NodeKind::Module { .. });
+
+    let mut moves = Vec::new();
+
+    for (canonical, public) in &self.graph.reexports {
+      // Ties go to the canonical path, so that items don't move needlessly.
+      let Some(public) = public
+        .iter()
+        .min_by_key(|path| (path.matches("::").count(), *path != canonical))
+        .filter(|&path| path != canonical)
+      else {
+        continue;
+      };
+
+      let Some((module, name)) = public.rsplit_once("::") else {
+        continue;
+      };
+
+      let Some(&module) = ids.get(module).and_then(|candidates| {
+        candidates
+          .iter()
+          .find(|&&id| is_module(id) && !self.declarations.contains(&id))
+      }) else {
+        continue;
+      };
+
+      for &id in ids.get(canonical.as_str()).into_iter().flatten() {
+        if let (false, Some(parent)) = (is_module(id), parents[id]) {
+          moves.push((id, parent, module, name.to_owned(), canonical.clone()));
+        }
+      }
+    }
+
+    if moves.is_empty() {
+      return;
+    }
+
+    for (id, parent, module, name, canonical) in moves {
+      self.graph.nodes[parent]
+        .children
+        .retain(|&child| child != id);
+      self.graph.nodes[module].children.push(id);
+
+      let node = &mut self.graph.nodes[id];
+      node.name = name;
+      node.definition_path = Some(canonical);
+    }
+
+    self.graph.renumber(self.config.id_order);
+  }
+
+  /// Link each bounded node to the traits its bounds name, preferring a trait
+  /// in the same module, then any trait in the crate with that name.
+  fn resolve_bounds(&mut self) {
+    for (node_id, module_id, bounds) in take(&mut self.bounds) {
+      for bound in bounds {
+        let name = bound.rsplit("::").next().unwrap();
+
+        let target = self.find(module_id, name, |kind| {
+          matches!(kind, NodeKind::Trait { .. } | NodeKind::TraitAlias { .. })
+        });
+
+        if let Some(target) = target {
+          self.graph.nodes[node_id].add_edge(target, EdgeKind::Bound);
+        }
+      }
+    }
+  }
+
+  /// Link each impl block to its self type, if it's in the crate, and each
+  /// in-crate type to the in-crate traits it implements. Traits implemented
+  /// for types outside the crate record those types' names.
+  fn resolve_implementations(&mut self) {
+    for (impl_id, module_id, ty, name) in take(&mut self.implementations) {
+      let type_id =
+        self.find(module_id, ty.rsplit("::").next().unwrap(), Self::is_type);
+
+      if let Some(type_id) = type_id {
+        self.graph.nodes[impl_id].add_edge(type_id, EdgeKind::SelfType);
+      }
+
+      let Some(name) = name else {
+        continue;
+      };
+
+      let trait_name = name.rsplit("::").next().unwrap();
+
+      if MARKER_TRAITS.contains(&trait_name) {
+        let Some(type_id) = type_id else {
+          continue;
+        };
+
+        if let NodeKind::Struct { marker_traits, .. }
+        | NodeKind::Enum { marker_traits, .. } =
+          &mut self.graph.nodes[type_id].kind
+        {
+          if !marker_traits.iter().any(|marker| marker == trait_name) {
+            marker_traits.push(trait_name.to_owned());
+          }
+        }
+
+        continue;
+      }
+
+      let trait_id = self.find(module_id, trait_name, |kind| {
+        matches!(kind, NodeKind::Trait { .. })
+      });
+
+      let Some(trait_id) = trait_id else {
+        continue;
+      };
+
+      let Some(type_id) = type_id else {
+        if let NodeKind::Trait {
+          external_implementors,
+          ..
+        } = &mut self.graph.nodes[trait_id].kind
+        {
+          if !external_implementors.contains(&ty) {
+            external_implementors.push(ty);
+          }
+        }
+
+        continue;
+      };
+
+      self.graph.nodes[type_id].add_edge(trait_id, EdgeKind::Implements);
+    }
+  }
+
+  fn is_type(kind: &NodeKind) -> bool {
+    matches!(
+      kind,
+      NodeKind::Struct { .. }
+        | NodeKind::Enum { .. }
+        | NodeKind::Union { .. }
+        | NodeKind::Type { .. }
+    )
+  }
+
+  /// Find a node named `name` whose kind satisfies `predicate`, preferring
+  /// one in `module_id`, then any in the crate.
+  fn find(
+    &self,
+    module_id: NodeId,
+    name: &str,
+    predicate: fn(&NodeKind) -> bool,
+  ) -> Option<NodeId> {
+    let matches = |id: &NodeId| {
+      let node = &self.graph.nodes[*id];
+      node.name == name && predicate(&node.kind)
+    };
+
+    self.graph.nodes[module_id]
+      .children
+      .iter()
+      .copied()
+      .find(matches)
+      .or_else(|| (0..self.graph.nodes.len()).find(matches))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn analyze(source: &str) -> Graph {
+    Analyzer::new(&Config::default())
+      .analyze_source("lib.rs", source.into())
+      .unwrap()
+  }
+
+  fn node<'a>(graph: &'a Graph, name: &str) -> &'a Node {
+    graph.nodes.iter().find(|node| node.name == name).unwrap()
+  }
+
+  fn uses(graph: &Graph, name: &str) -> Vec<String> {
+    node(graph, name)
+      .edges
+      .iter()
+      .filter(|edge| edge.kind == EdgeKind::Uses)
+      .map(|edge| graph.nodes[edge.target].name.clone())
+      .collect()
+  }
+
+  #[test]
+  fn generic_associated_type() {
+    let graph = analyze(
+      "
+      trait Lend {
+        type Item<'a> where Self: 'a;
+      }
+      ",
+    );
+
+    let item = node(&graph, "Item");
+
+    assert_eq!(
+      item.kind,
+      NodeKind::Type {
+        generics: "<'a>".into(),
+      }
+    );
+
+    assert_eq!(item.lifetimes, ["'a"]);
+
+    assert_eq!(
+      item
+        .where_clause
+        .iter()
+        .map(|predicate| predicate.rendered.as_str())
+        .collect::<Vec<&str>>(),
+      ["Self: 'a"],
+    );
+
+    assert_eq!(graph.nodes[item.id - 1].name, "Lend");
+    assert_eq!(graph.nodes[item.id - 1].children, [item.id]);
+  }
+
+  #[test]
+  fn bounded_associated_type() {
+    let graph = analyze(
+      "
+      trait Show {}
+
+      trait Render {
+        type Output: Show + Iterator<Item = u8>;
+      }
+      ",
+    );
+
+    let show = node(&graph, "Show").id;
+
+    let output = node(&graph, "Output");
+
+    assert_eq!(output.bounds, ["Show", "Iterator<Item = u8>"]);
+
+    assert_eq!(
+      output
+        .edges
+        .iter()
+        .map(|edge| (edge.target, edge.kind))
+        .collect::<Vec<(NodeId, EdgeKind)>>(),
+      [(show, EdgeKind::Bound)],
+    );
+  }
+
+  #[test]
+  fn where_clause_bounds() {
+    let graph = analyze(
+      "
+      trait Encode {}
+
+      fn write<T>(value: T)
+      where
+        T: Encode + Clone,
+      {
+      }
+      ",
+    );
+
+    let write = node(&graph, "write");
+
+    assert_eq!(write.bounds, ["Encode", "Clone"]);
+
+    assert_eq!(
+      write
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == EdgeKind::Bound)
+        .map(|edge| edge.target)
+        .collect::<Vec<NodeId>>(),
+      [node(&graph, "Encode").id],
+    );
+  }
+
+  #[test]
+  fn bounds_keep_generic_arguments_and_binders() {
+    let graph = analyze(
+      "
+      fn parse<I: Iterator<Item = u8>, F>(input: I, f: F)
+      where
+        F: for<'a> Fn(&'a str) -> u8,
+      {
+      }
+      ",
+    );
+
+    assert_eq!(
+      node(&graph, "parse").bounds,
+      ["Iterator<Item = u8>", "for<'a> Fn(&'a str) -> u8"],
+    );
+  }
+
+  #[test]
+  fn method_dependencies_are_their_own() {
+    let graph = analyze(
+      "
+      struct Config;
+      struct Parser;
+
+      impl Config {
+        fn parse(&self) -> Parser {
+          Parser
+        }
+      }
+      ",
+    );
+
+    assert_eq!(uses(&graph, "parse"), ["Parser"]);
+    assert_eq!(uses(&graph, "impl Config"), ["Config"]);
+    assert!(uses(&graph, "Config").is_empty());
+  }
+
+  #[test]
+  fn impls_of_foreign_types_are_traced() {
+    let graph = analyze(
+      "
+      struct Meters(u32);
+
+      impl From<Meters> for u32 {
+        fn from(meters: Meters) -> u32 {
+          meters.0
+        }
+      }
+      ",
+    );
+
+    assert_eq!(uses(&graph, "impl From<Meters> for u32"), ["Meters"]);
+    assert_eq!(uses(&graph, "from"), ["Meters"]);
+  }
+
+  #[test]
+  fn unsafe_impls_and_traits() {
+    let graph = analyze(
+      "
+      unsafe trait Zeroable {}
+
+      struct Handle(*mut u8);
+
+      unsafe impl Send for Handle {}
+
+      unsafe impl Zeroable for Handle {}
+
+      impl Handle {}
+      ",
+    );
+
+    assert!(matches!(
+      node(&graph, "Zeroable").kind,
+      NodeKind::Trait {
+        is_unsafe: true,
+        ..
+      }
+    ));
+
+    let handle = node(&graph, "Handle").id;
+
+    let unsafe_impls = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          NodeKind::Impl {
+            is_unsafe: true,
+            ..
+          }
+        )
+      })
+      .collect::<Vec<&Node>>();
+
+    assert_eq!(
+      unsafe_impls
+        .iter()
+        .map(|node| node.kind.clone())
+        .collect::<Vec<NodeKind>>(),
+      [
+        NodeKind::Impl {
+          self_type: "Handle".into(),
+          trait_name: Some("Send".into()),
+          is_unsafe: true,
+        },
+        NodeKind::Impl {
+          self_type: "Handle".into(),
+          trait_name: Some("Zeroable".into()),
+          is_unsafe: true,
+        },
+      ],
+    );
+
+    for node in unsafe_impls {
+      assert!(node
+        .edges
+        .iter()
+        .any(|edge| edge.target == handle && edge.kind == EdgeKind::SelfType));
+    }
+  }
+
+  #[test]
+  fn trait_methods() {
+    let graph = analyze(
+      "
+      trait Buffer {
+        fn len(&self) -> usize;
+
+        fn first(&self) -> u8 {
+          unsafe { *self.as_ptr() }
+        }
+
+        fn clear(&mut self) {
+          panic!()
+        }
+      }
+      ",
+    );
+
+    let buffer = node(&graph, "Buffer");
+
+    assert_eq!(
+      buffer
+        .children
+        .iter()
+        .map(|&child| graph.nodes[child].name.as_str())
+        .collect::<Vec<&str>>(),
+      ["len", "first", "clear"],
+    );
+
+    let counts = |name: &str| match node(&graph, name).kind {
+      NodeKind::Function {
+        unsafe_block_count,
+        panic_count,
+        ..
+      } => (unsafe_block_count, panic_count),
+      _ => panic!("`{name}` is not a function"),
+    };
+
+    assert_eq!(counts("len"), (0, 0));
+    assert_eq!(counts("first"), (1, 0));
+    assert_eq!(counts("clear"), (0, 1));
+  }
+
+  #[test]
+  fn impl_method_churn() {
+    let dir = env::temp_dir().join(format!("eye-churn-{}", std::process::id()));
+
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let git = |arguments: &[&str]| {
+      assert!(Command::new("git")
+        .arg("-C")
+        .arg(&dir)
+        .args(["-c", "user.name=eye", "-c", "user.email=eye@example.com"])
+        .args(arguments)
+        .output()
+        .unwrap()
+        .status
+        .success());
+    };
+
+    fs::write(
+      dir.join("Cargo.toml"),
+      "
+      [package]
+      name = \"churn\"
+      version = \"0.0.0\"
+      edition = \"2021\"
+      ",
+    )
+    .unwrap();
+
+    let source = |value: u32| {
+      format!(
+        "
+        pub struct Counter;
+
+        impl Counter {{
+          pub fn get(&self) -> u32 {{
+            {value}
+          }}
+
+          pub fn reset(&self) {{}}
+        }}
+        "
+      )
+    };
+
+    git(&["init", "--quiet"]);
+
+    for value in [1, 2] {
+      fs::write(dir.join("src/lib.rs"), source(value)).unwrap();
+      git(&["add", "."]);
+      git(&["commit", "--quiet", "--message", "Change"]);
+    }
+
+    let graph = crate::analyze_with(
+      &dir,
+      &Config {
+        git_churn: true,
+        ..Config::default()
+      },
+    )
+    .unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(node(&graph, "get").churn, Some(2));
+    assert_eq!(node(&graph, "reset").churn, Some(1));
+    assert_eq!(node(&graph, "impl Counter").churn, None);
+  }
+
+  #[test]
+  fn cfg_disabled_members() {
+    let graph = Analyzer::new(&Config {
+      cfg: vec!["unix".parse().unwrap()],
+      ..Config::default()
+    })
+    .analyze_source(
+      "lib.rs",
+      "
+      struct Handle {
+        fd: i32,
+        #[cfg(windows)]
+        handle: usize,
+      }
+
+      enum Platform {
+        #[cfg(unix)]
+        Unix,
+        #[cfg(windows)]
+        Windows,
+      }
+
+      trait Open {
+        #[cfg(windows)]
+        fn open_windows(&self);
+
+        fn open(&self);
+      }
+
+      impl Handle {
+        #[cfg(windows)]
+        fn raw(&self) -> usize {
+          self.handle
+        }
+
+        fn fd(&self) -> i32 {
+          self.fd
+        }
+      }
+      "
+      .into(),
+    )
+    .unwrap();
+
+    let handle = node(&graph, "Handle");
+
+    assert_eq!(handle.member_count, Some(1));
+    assert!(matches!(
+      &handle.kind,
+      NodeKind::Struct { fields, .. } if fields.len() == 1
+    ));
+
+    let platform = node(&graph, "Platform");
+
+    assert_eq!(platform.member_count, Some(1));
+    assert!(matches!(
+      &platform.kind,
+      NodeKind::Enum { variants, .. } if *variants == ["Unix"]
+    ));
+
+    assert_eq!(node(&graph, "Open").member_count, Some(1));
+    assert_eq!(node(&graph, "impl Handle").member_count, Some(1));
+
+    assert!(!graph
+      .nodes
+      .iter()
+      .any(|node| node.name == "open_windows" || node.name == "raw"));
+  }
+
+  #[test]
+  fn cfg_disabled_modules_in_src_dir() {
+    let dir = env::temp_dir().join(format!("eye-cfg-{}", std::process::id()));
+
+    fs::create_dir_all(dir.join("src/windows")).unwrap();
+
+    fs::write(
+      dir.join("Cargo.toml"),
+      "
+      [package]
+      name = \"platform\"
+      version = \"0.0.0\"
+      edition = \"2021\"
+      ",
+    )
+    .unwrap();
+
+    fs::write(
+      dir.join("src/lib.rs"),
+      "
+      mod unix;
+
+      #[cfg(windows)]
+      mod windows;
+      ",
+    )
+    .unwrap();
+
+    fs::write(dir.join("src/unix.rs"), "fn unix() {}").unwrap();
+    fs::write(dir.join("src/windows.rs"), "mod registry;").unwrap();
+    fs::write(dir.join("src/windows/registry.rs"), "fn registry() {}").unwrap();
+
+    let graph = crate::analyze_with(
+      &dir,
+      &Config {
+        cfg: vec!["unix".parse().unwrap()],
+        src_dir: Some("src".into()),
+        ..Config::default()
+      },
+    )
+    .unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let names = graph
+      .nodes
+      .iter()
+      .map(|node| node.name.as_str())
+      .collect::<Vec<&str>>();
+
+    assert!(names.contains(&"unix"));
+    assert!(!names.contains(&"registry"));
+    assert!(!names.contains(&"windows.rs"));
+  }
+
+  #[test]
+  fn recursive_methods() {
+    let graph = analyze(
+      "
+      struct Tree;
+
+      impl Tree {
+        fn depth(&self) -> u32 {
+          self.children()
+        }
+
+        fn children(&self) -> u32 {
+          self.depth()
+        }
+      }
+      ",
+    );
+
+    assert!(matches!(
+      node(&graph, "depth").kind,
+      NodeKind::Function {
+        is_recursive: true,
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn reachable_through_private_return_type() {
+    let source = "
+      pub fn make() -> Secret {
+        Secret
+      }
+
+      struct Secret;
+
+      struct Unused;
+      ";
+
+    let graph = analyze(source);
+
+    assert!(node(&graph, "Secret").publicly_reachable);
+    assert!(!node(&graph, "Unused").publicly_reachable);
+
+    let graph = Analyzer::new(&Config {
+      reachable_only: true,
+      ..Config::default()
+    })
+    .analyze_source("lib.rs", source.into())
+    .unwrap();
+
+    assert!(graph.nodes.iter().any(|node| node.name == "Secret"));
+    assert!(!graph.nodes.iter().any(|node| node.name == "Unused"));
+  }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+/// Collects metrics about a function body. Nested items are separate nodes,
+/// so their bodies are not visited.
+#[derive(Default)]
+pub(crate) struct BodyVisitor {
+  pub(crate) panics: u32,
+  pub(crate) todo_count: u32,
+  pub(crate) unimplemented: u32,
+  pub(crate) unsafe_blocks: u32,
+}
+
+impl BodyVisitor {
+  pub(crate) fn visit(block: &Block) -> Self {
+    let mut visitor = Self::default();
+    visitor.visit_block(block);
+    visitor
+  }
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+  fn visit_expr_unsafe(&mut self, i: &'ast ExprUnsafe) {
+    self.unsafe_blocks += 1;
+    syn::visit::visit_expr_unsafe(self, i);
+  }
+
+  fn visit_macro(&mut self, i: &'ast Macro) {
+    if let Some(segment) = i.path.segments.last() {
+      if segment.ident == "panic" {
+        self.panics += 1;
+      } else if segment.ident == "todo" {
+        self.todo_count += 1;
+      } else if segment.ident == "unimplemented" {
+        self.unimplemented += 1;
+      }
+    }
+  }
+
+  fn visit_item(&mut self, _: &'ast Item) {}
+}
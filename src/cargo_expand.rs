@@ -0,0 +1,54 @@
+use super::*;
+
+/// Fail, explaining how to install it, unless `cargo expand` can be run.
+pub(crate) fn check() -> Result {
+  let available = Command::new("cargo")
+    .args(["expand", "--version"])
+    .output()
+    .is_ok_and(|output| output.status.success());
+
+  if !available {
+    bail!(
+      "`--expand` requires `cargo expand`, which can be installed with \
+       `cargo install cargo-expand`"
+    );
+  }
+
+  Ok(())
+}
+
+/// The source of `target`, in the package whose manifest is
+/// `manifest_path`, as a single file with every macro expanded and every
+/// module inline, or `None` for targets that can't be expanded, such as
+/// build scripts.
+pub(crate) fn target(
+  manifest_path: &Path,
+  target: &Target,
+) -> Result<Option<String>> {
+  let selector = match target.kind.first().map(String::as_str) {
+    Some("custom-build") => return Ok(None),
+    Some(kind @ ("bin" | "example" | "test" | "bench")) => {
+      vec![format!("--{kind}"), target.name.clone()]
+    }
+    _ => vec!["--lib".to_owned()],
+  };
+
+  let output = Command::new("cargo")
+    .arg("expand")
+    .arg("--manifest-path")
+    .arg(manifest_path)
+    .args(&selector)
+    .args(["--color", "never"])
+    .output()
+    .map_err(|error| anyhow!("failed to run cargo expand: {error}"))?;
+
+  if !output.status.success() {
+    bail!(
+      "cargo expand {} failed: {}",
+      selector.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+
+  Ok(Some(String::from_utf8(output.stdout)?))
+}
@@ -0,0 +1,129 @@
+use super::*;
+
+/// A configuration option set for conditional compilation, either a bare
+/// name, like `unix`, or a name and value, like `feature="x"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cfg {
+  pub name: String,
+  pub value: Option<String>,
+}
+
+impl Cfg {
+  /// Evaluate a `cfg` predicate against the options in `cfgs`. Predicates
+  /// that can't be understood are considered true.
+  pub(crate) fn evaluate(cfgs: &[Cfg], predicate: &NestedMeta) -> bool {
+    let NestedMeta::Meta(meta) = predicate else {
+      return true;
+    };
+
+    match meta {
+      Meta::Path(path) => cfgs
+        .iter()
+        .any(|cfg| cfg.value.is_none() && path.is_ident(&cfg.name)),
+      Meta::NameValue(MetaNameValue {
+        path,
+        lit: Lit::Str(value),
+        ..
+      }) => cfgs.iter().any(|cfg| {
+        path.is_ident(&cfg.name) && cfg.value == Some(value.value())
+      }),
+      Meta::NameValue(_) => true,
+      Meta::List(list) => {
+        let mut nested = list.nested.iter();
+
+        if list.path.is_ident("all") {
+          nested.all(|predicate| Self::evaluate(cfgs, predicate))
+        } else if list.path.is_ident("any") {
+          nested.any(|predicate| Self::evaluate(cfgs, predicate))
+        } else if list.path.is_ident("not") && list.nested.len() == 1 {
+          !Self::evaluate(cfgs, &list.nested[0])
+        } else {
+          true
+        }
+      }
+    }
+  }
+}
+
+impl FromStr for Cfg {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (name, value) = match s.split_once('=') {
+      Some((name, value)) => (
+        name.trim(),
+        Some(value.trim().trim_matches('"').to_string()),
+      ),
+      None => (s.trim(), None),
+    };
+
+    if name.is_empty() {
+      bail!("cfg option `{s}` has no name");
+    }
+
+    Ok(Self {
+      name: name.to_string(),
+      value,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(predicate: &str, expected: bool) {
+    let cfgs = ["unix", "feature=\"serde\""]
+      .iter()
+      .map(|cfg| cfg.parse().unwrap())
+      .collect::<Vec<Cfg>>();
+
+    assert_eq!(
+      Cfg::evaluate(&cfgs, &parse_str(predicate).unwrap()),
+      expected,
+      "{predicate}",
+    );
+  }
+
+  #[test]
+  fn names() {
+    case("unix", true);
+    case("windows", false);
+    case("feature", false);
+  }
+
+  #[test]
+  fn names_and_values() {
+    case("feature = \"serde\"", true);
+    case("feature = \"std\"", false);
+    case("unix = \"serde\"", false);
+  }
+
+  #[test]
+  fn all() {
+    case("all()", true);
+    case("all(unix, feature = \"serde\")", true);
+    case("all(unix, windows)", false);
+  }
+
+  #[test]
+  fn any() {
+    case("any()", false);
+    case("any(windows, unix)", true);
+    case("any(windows, feature = \"std\")", false);
+  }
+
+  #[test]
+  fn not() {
+    case("not(windows)", true);
+    case("not(unix)", false);
+    case("not(any(windows, all(unix, feature = \"serde\")))", false);
+  }
+
+  #[test]
+  fn unknown_predicates_hold() {
+    case("version(\"1.0\")", true);
+    case("feature = 1", true);
+  }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+/// When to color human-readable output, such as that of `stats`, with ANSI
+/// escape codes. Machine-readable output, such as exported graphs and the
+/// tab-separated lines of `list` and `public-api`, is never colored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum Color {
+  /// Color if standard output is a terminal and `NO_COLOR` isn't set
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+impl Color {
+  /// Decide whether `Auto` means `Always` or `Never`.
+  pub(crate) fn resolve(self) -> Self {
+    match self {
+      Self::Auto
+        if io::stdout().is_terminal()
+          && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) =>
+      {
+        Self::Always
+      }
+      Self::Auto => Self::Never,
+      color => color,
+    }
+  }
+
+  pub(crate) fn bold(self, text: impl Display) -> String {
+    self.paint("1", text)
+  }
+
+  fn paint(self, code: &str, text: impl Display) -> String {
+    match self {
+      Self::Always => format!("\x1b[{code}m{text}\x1b[0m"),
+      Self::Auto | Self::Never => text.to_string(),
+    }
+  }
+}
@@ -0,0 +1,102 @@
+use super::*;
+
+/// Settings that control how a crate is analyzed.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+  /// Keep file paths absolute, rather than making them relative to the
+  /// analyzed crate or workspace.
+  pub absolute_paths: bool,
+  /// A JSON file mapping glob patterns of canonical paths, such as
+  /// `app::db::*`, to objects whose fields are merged into the annotations
+  /// of every matching node.
+  pub annotations: Option<PathBuf>,
+  /// Name of the only binary target to analyze, rather than every target.
+  /// Packages without it are left out.
+  pub bin: Option<String>,
+  /// Options set for conditional compilation. If any are given, items whose
+  /// `#[cfg(...)]` attributes evaluate to false are left out, otherwise all
+  /// items are kept.
+  pub cfg: Vec<Cfg>,
+  /// Show re-exported items under their shortest public path, like rustdoc
+  /// does, rather than where they're defined. Modules stay where they are.
+  pub collapse_reexports: bool,
+  /// Analyze the source of each target as expanded by `cargo expand`, which
+  /// must be installed, so that items generated by any macro appear. Build
+  /// scripts, and targets that fail to expand, are analyzed as written.
+  pub expand: bool,
+  /// Expand invocations of the crate's own `macro_rules!` macros, so that
+  /// the items they define appear in the graph. Only rules without
+  /// repetitions are supported.
+  pub expand_macros: bool,
+  /// Names of node kinds to drop from the graph. The children of a dropped
+  /// node are moved up to its parent.
+  pub exclude_kinds: Vec<String>,
+  /// Count the commits that touched each function and type definition,
+  /// which requires the crate to be in a git repository.
+  pub git_churn: bool,
+  /// Make every module a direct child of its package, named by its full
+  /// path, rather than nesting modules.
+  pub flatten: bool,
+  /// How node ids are assigned, by a traversal of the containment tree
+  /// after children are sorted.
+  pub id_order: IdOrder,
+  /// Skip source files larger than this many bytes, such as generated code,
+  /// leaving a placeholder module for each without its items.
+  pub max_file_size: Option<u64>,
+  /// A file holding the output of `cargo metadata --no-deps
+  /// --format-version 1` for the crate, to read instead of running cargo.
+  pub metadata: Option<PathBuf>,
+  /// Only keep nodes whose name matches this, along with their ancestors, so
+  /// that they're still reachable from the root.
+  pub name_filter: Option<Regex>,
+  /// Skip tracing the paths items refer to, leaving out `uses` edges for a
+  /// faster, purely structural graph.
+  pub no_deps_edges: bool,
+  /// Analyze source files even if `.gitignore` or `.ignore` rules exclude
+  /// them, whether they are found through `mod` declarations or by walking
+  /// [`Config::src_dir`].
+  pub no_ignore: bool,
+  /// Only keep nodes marked as changed since [`Config::since`], along with
+  /// their ancestors.
+  pub only_changed: bool,
+  /// Names of the workspace members to analyze, defaulting to all of them.
+  /// A single member is the root of the graph, rather than the workspace.
+  pub packages: Vec<String>,
+  /// Name in-crate types in struct fields and function signatures by their
+  /// canonical paths, such as `crate::server::Config`, rather than as
+  /// written.
+  pub qualify_types: bool,
+  /// Only keep items reachable from outside the crate, as
+  /// [`Node::publicly_reachable`] describes, along with their ancestors.
+  pub reachable_only: bool,
+  /// Patterns whose matches in each node's source code and documentation
+  /// are replaced with `[REDACTED]`, after parsing. This is best-effort:
+  /// anything not matched, such as a secret split across lines, or found
+  /// elsewhere, such as in a name or a warning, is kept.
+  pub redact: Vec<Regex>,
+  /// Only link items to those visible from their module, rather than to any
+  /// item with a matching name.
+  pub respect_visibility: bool,
+  /// Only analyze each target's crate root file, rather than following its
+  /// `mod` declarations to other files. Inline modules in the root are still
+  /// analyzed.
+  pub root_module_only: bool,
+  /// Git revision to compare the working tree to, marking nodes whose lines
+  /// differ as changed.
+  pub since: Option<String>,
+  /// How each node's children are ordered.
+  pub sort: Sort,
+  /// Directory, relative to each package's manifest, in which to analyze
+  /// every source file, instead of following `mod` declarations from the
+  /// root file of each cargo target.
+  pub src_dir: Option<PathBuf>,
+  /// Fail if analysis produces any warnings, such as for files that can't be
+  /// parsed, rather than returning a graph without them.
+  pub strict: bool,
+  /// Give up on analysis that takes longer than this, failing with
+  /// [`TimedOut`] rather than returning a partial graph.
+  pub timeout: Option<Duration>,
+  /// Only keep items at least this visible, along with their ancestors, so
+  /// that `Crate` keeps both `pub` and `pub(crate)` items.
+  pub visibility: Option<Visibility>,
+}
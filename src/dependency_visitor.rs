@@ -0,0 +1,662 @@
+use super::*;
+
+/// Standard library macros whose arguments are all expressions, separated by
+/// commas or, as in `vec![x; n]`, a semicolon, so that their arguments can be
+/// traced like any other expression. Format strings are plain literals, so
+/// variables captured inline, like `{name}`, aren't traced, and named
+/// arguments, like `name = value`, only trace their value. Other macros'
+/// arguments could be anything, and are skipped.
+const EXPRESSION_MACROS: &[&str] = &[
+  "assert",
+  "assert_eq",
+  "assert_ne",
+  "dbg",
+  "debug_assert",
+  "debug_assert_eq",
+  "debug_assert_ne",
+  "eprint",
+  "eprintln",
+  "format",
+  "format_args",
+  "panic",
+  "print",
+  "println",
+  "unreachable",
+  "vec",
+  "write",
+  "writeln",
+];
+
+pub(crate) struct DependencyVisitor<'a> {
+  graph: &'a mut Graph,
+  current_id: NodeId,
+  current_module_id: NodeId,
+  /// The type that `Self` refers to, inside an impl block for an in-crate
+  /// type.
+  self_type: Option<NodeId>,
+  /// The impl block being visited, if any.
+  impl_id: Option<NodeId>,
+  /// The impl blocks of each in-crate type, whose methods and associated
+  /// consts paths like `Type::item` name.
+  impls: &'a HashMap<NodeId, Vec<NodeId>>,
+  /// Names bound by patterns in each enclosing scope, which shadow items.
+  locals: Vec<HashSet<String>>,
+  /// Canonical node paths, present when only visible items may be linked.
+  paths: Option<&'a [Option<String>]>,
+}
+
+impl<'a> DependencyVisitor<'a> {
+  pub(crate) fn new(
+    graph: &'a mut Graph,
+    current_id: NodeId,
+    current_module_id: NodeId,
+    self_type: Option<NodeId>,
+    impls: &'a HashMap<NodeId, Vec<NodeId>>,
+    paths: Option<&'a [Option<String>]>,
+  ) -> Self {
+    let impl_id = matches!(graph.nodes[current_id].kind, NodeKind::Impl { .. })
+      .then_some(current_id);
+
+    Self {
+      graph,
+      current_id,
+      current_module_id,
+      self_type,
+      impl_id,
+      impls,
+      locals: Vec::new(),
+      paths,
+    }
+  }
+
+  fn bind(&mut self, pat: &Pat) {
+    if self.locals.is_empty() {
+      self.locals.push(HashSet::new());
+    }
+
+    self
+      .locals
+      .last_mut()
+      .unwrap()
+      .extend(PatternVisitor::visit(pat));
+  }
+
+  fn is_local(&self, name: &str) -> bool {
+    self.locals.iter().any(|scope| scope.contains(name))
+  }
+
+  fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+    self.locals.push(HashSet::new());
+    f(self);
+    self.locals.pop();
+  }
+
+  fn visit_function(
+    &mut self,
+    sig: &syn::Signature,
+    f: impl FnOnce(&mut Self),
+  ) {
+    self.scoped(|visitor| {
+      for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+          visitor.bind(&pat_type.pat);
+        }
+      }
+
+      f(visitor);
+    });
+  }
+
+  fn find_node_by_name(&self, name: &str) -> Option<NodeId> {
+    self
+      .graph
+      .nodes
+      .iter()
+      .position(|node| node.name == name && self.is_visible(node.id))
+  }
+
+  /// Whether the target can be named from the current module. `pub(super)`
+  /// and `pub(in path)` are both treated as visible from the parent of the
+  /// target's module.
+  fn is_visible(&self, target_id: NodeId) -> bool {
+    let Some(paths) = self.paths else {
+      return true;
+    };
+
+    let (Some(target), Some(module)) =
+      (&paths[target_id], &paths[self.current_module_id])
+    else {
+      return true;
+    };
+
+    let within = |ancestor: Option<&str>| {
+      ancestor.is_some_and(|ancestor| {
+        module == ancestor || module.starts_with(&format!("{ancestor}::"))
+      })
+    };
+
+    let parent = target.rsplit_once("::").map(|(parent, _)| parent);
+
+    match self.graph.nodes[target_id].visibility {
+      Visibility::Public => true,
+      Visibility::Crate => within(target.split("::").next()),
+      Visibility::Restricted => within(parent.map(|parent| {
+        parent
+          .rsplit_once("::")
+          .map_or(parent, |(grandparent, _)| grandparent)
+      })),
+      Visibility::Private => within(parent),
+    }
+  }
+
+  fn find_node_in_module(
+    &self,
+    module_id: NodeId,
+    name: &str,
+  ) -> Option<NodeId> {
+    self.graph.nodes[module_id]
+      .children
+      .iter()
+      .find(|&&child_id| {
+        self.graph.nodes[child_id].name == name && self.is_visible(child_id)
+      })
+      .cloned()
+  }
+
+  fn is_self(expr: &syn::Expr) -> bool {
+    matches!(expr, syn::Expr::Path(path) if path.path.is_ident("self"))
+  }
+
+  /// The arguments of an invocation of one of [`EXPRESSION_MACROS`], or
+  /// nothing if it's another macro or its arguments don't parse.
+  fn macro_arguments(mac: &Macro) -> Vec<syn::Expr> {
+    if !mac.path.segments.last().is_some_and(|segment| {
+      EXPRESSION_MACROS.contains(&segment.ident.to_string().as_str())
+    }) {
+      return Vec::new();
+    }
+
+    let parser = |input: ParseStream| {
+      let mut arguments = Vec::new();
+
+      while !input.is_empty() {
+        arguments.push(input.parse()?);
+
+        if input.is_empty() {
+          break;
+        }
+
+        let lookahead = input.lookahead1();
+
+        if lookahead.peek(syn::Token![,]) {
+          input.parse::<syn::Token![,]>()?;
+        } else if lookahead.peek(syn::Token![;]) {
+          input.parse::<syn::Token![;]>()?;
+        } else {
+          return Err(lookahead.error());
+        }
+      }
+
+      Ok(arguments)
+    };
+
+    parser.parse2(mac.tokens.clone()).unwrap_or_default()
+  }
+
+  /// The method or associated const that `Self::name` or `self.name()`
+  /// refers to, inside an impl block: one of the block's own, or else one of
+  /// the self type's other impl blocks.
+  fn find_self_item(&self, name: &str) -> Option<NodeId> {
+    self
+      .impl_id
+      .and_then(|impl_id| self.find_item(impl_id, name))
+      .or_else(|| self.find_type_item(self.self_type?, name))
+  }
+
+  /// The method or associated const of one of an in-crate type's impl
+  /// blocks that `Type::name` refers to.
+  fn find_type_item(&self, type_id: NodeId, name: &str) -> Option<NodeId> {
+    self
+      .impls
+      .get(&type_id)?
+      .iter()
+      .find_map(|&impl_id| self.find_item(impl_id, name))
+  }
+
+  fn find_item(&self, impl_id: NodeId, name: &str) -> Option<NodeId> {
+    self.graph.nodes[impl_id]
+      .children
+      .iter()
+      .copied()
+      .find(|&id| self.graph.nodes[id].name == name)
+  }
+
+  fn add_dependency(&mut self, target_id: NodeId) {
+    self.graph.nodes[self.current_id].add_edge(target_id, EdgeKind::Uses);
+  }
+}
+
+impl<'ast> Visit<'ast> for DependencyVisitor<'_> {
+  fn visit_path(&mut self, path: &'ast syn::Path) {
+    if let Some(ident) = path.get_ident() {
+      let name = ident.to_string();
+
+      if name == "Self" {
+        if let Some(self_type) = self.self_type {
+          self.add_dependency(self_type);
+        }
+      } else if let Some(target_id) =
+        self.find_node_in_module(self.current_module_id, &name)
+      {
+        self.add_dependency(target_id);
+      } else {
+        if let Some(target_id) = self.find_node_by_name(&name) {
+          self.add_dependency(target_id);
+        }
+      }
+    } else {
+      let mut segments = path.segments.iter().peekable();
+
+      // `Self::item` names an item of an impl block of the self type, or of
+      // the one being visited, for a type outside the crate.
+      if segments
+        .peek()
+        .is_some_and(|segment| segment.ident == "Self")
+      {
+        segments.next();
+
+        if let Some(target_id) = segments
+          .next()
+          .and_then(|segment| self.find_self_item(&segment.ident.to_string()))
+        {
+          self.add_dependency(target_id);
+        } else if let Some(self_type) = self.self_type {
+          self.add_dependency(self_type);
+        }
+      } else {
+        let mut current_module_id = self.current_module_id;
+
+        for segment in segments {
+          let name = segment.ident.to_string();
+          if let Some(target_id) = self
+            .find_node_in_module(current_module_id, &name)
+            .or_else(|| self.find_type_item(current_module_id, &name))
+          {
+            self.add_dependency(target_id);
+            current_module_id = target_id;
+          } else {
+            break;
+          }
+        }
+      }
+    }
+
+    syn::visit::visit_path(self, path);
+  }
+
+  /// The dependencies of an impl block's methods and associated consts are
+  /// their own, rather than the block's, which keeps those of its header and
+  /// other items.
+  fn visit_impl_item(&mut self, i: &'ast ImplItem) {
+    let name = match i {
+      ImplItem::Const(i) => Some(&i.ident),
+      ImplItem::Method(i) => Some(&i.sig.ident),
+      _ => None,
+    };
+
+    let item_id = name
+      .zip(self.impl_id.filter(|&impl_id| impl_id == self.current_id))
+      .and_then(|(name, impl_id)| self.find_item(impl_id, &name.to_string()));
+
+    match item_id {
+      Some(item_id) => {
+        let impl_id = replace(&mut self.current_id, item_id);
+        syn::visit::visit_impl_item(self, i);
+        self.current_id = impl_id;
+      }
+      None => syn::visit::visit_impl_item(self, i),
+    }
+  }
+
+  /// A method called on `self` is one of the self type's, and a field of
+  /// `self` belongs to the self type.
+  fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+    if Self::is_self(&i.receiver) {
+      if let Some(target_id) = self.find_self_item(&i.method.to_string()) {
+        self.add_dependency(target_id);
+      }
+    }
+
+    syn::visit::visit_expr_method_call(self, i);
+  }
+
+  fn visit_expr_field(&mut self, i: &'ast syn::ExprField) {
+    if Self::is_self(&i.base) {
+      if let Some(self_type) = self.self_type {
+        self.add_dependency(self_type);
+      }
+    }
+
+    syn::visit::visit_expr_field(self, i);
+  }
+
+  fn visit_item(&mut self, i: &'ast syn::Item) {
+    let locals = take(&mut self.locals);
+    syn::visit::visit_item(self, i);
+    self.locals = locals;
+  }
+
+  fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+    self.visit_function(&i.sig, |visitor| {
+      syn::visit::visit_item_fn(visitor, i);
+    });
+  }
+
+  fn visit_impl_item_method(&mut self, i: &'ast syn::ImplItemMethod) {
+    self.visit_function(&i.sig, |visitor| {
+      syn::visit::visit_impl_item_method(visitor, i);
+    });
+  }
+
+  fn visit_trait_item_method(&mut self, i: &'ast syn::TraitItemMethod) {
+    self.visit_function(&i.sig, |visitor| {
+      syn::visit::visit_trait_item_method(visitor, i);
+    });
+  }
+
+  fn visit_block(&mut self, i: &'ast Block) {
+    self.scoped(|visitor| syn::visit::visit_block(visitor, i));
+  }
+
+  fn visit_local(&mut self, i: &'ast syn::Local) {
+    if let Some((_, init)) = &i.init {
+      self.visit_expr(init);
+    }
+
+    self.visit_pat(&i.pat);
+    self.bind(&i.pat);
+  }
+
+  fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+    self.scoped(|visitor| {
+      for input in &i.inputs {
+        visitor.bind(input);
+      }
+
+      syn::visit::visit_expr_closure(visitor, i);
+    });
+  }
+
+  fn visit_arm(&mut self, i: &'ast syn::Arm) {
+    self.scoped(|visitor| {
+      visitor.bind(&i.pat);
+      syn::visit::visit_arm(visitor, i);
+    });
+  }
+
+  fn visit_expr_let(&mut self, i: &'ast syn::ExprLet) {
+    self.visit_expr(&i.expr);
+    self.visit_pat(&i.pat);
+    self.bind(&i.pat);
+  }
+
+  fn visit_expr_if(&mut self, i: &'ast syn::ExprIf) {
+    self.scoped(|visitor| {
+      visitor.visit_expr(&i.cond);
+      visitor.visit_block(&i.then_branch);
+    });
+
+    if let Some((_, else_branch)) = &i.else_branch {
+      self.visit_expr(else_branch);
+    }
+  }
+
+  fn visit_expr_while(&mut self, i: &'ast syn::ExprWhile) {
+    self.scoped(|visitor| syn::visit::visit_expr_while(visitor, i));
+  }
+
+  fn visit_expr_for_loop(&mut self, i: &'ast syn::ExprForLoop) {
+    self.visit_expr(&i.expr);
+
+    self.scoped(|visitor| {
+      visitor.visit_pat(&i.pat);
+      visitor.bind(&i.pat);
+      visitor.visit_block(&i.body);
+    });
+  }
+
+  /// Paths that name a local binding refer to it rather than to an item.
+  fn visit_expr_path(&mut self, i: &'ast syn::ExprPath) {
+    if i.qself.is_none()
+      && i
+        .path
+        .get_ident()
+        .is_some_and(|ident| self.is_local(&ident.to_string()))
+    {
+      return;
+    }
+
+    syn::visit::visit_expr_path(self, i);
+  }
+
+  fn visit_macro(&mut self, mac: &'ast Macro) {
+    for argument in Self::macro_arguments(mac) {
+      match &argument {
+        syn::Expr::Assign(assign) if matches!(&*assign.left, syn::Expr::Path(path) if path.path.get_ident().is_some()) =>
+        {
+          self.visit_expr(&assign.right);
+        }
+        argument => self.visit_expr(argument),
+      }
+    }
+
+    syn::visit::visit_macro(self, mac);
+  }
+
+  fn visit_type(&mut self, ty: &'ast syn::Type) {
+    syn::visit::visit_type(self, ty);
+  }
+
+  fn visit_expr(&mut self, expr: &'ast syn::Expr) {
+    syn::visit::visit_expr(self, expr);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn uses(source: &str, name: &str) -> Vec<String> {
+    uses_with(&Config::default(), source, name)
+  }
+
+  fn uses_with(config: &Config, source: &str, name: &str) -> Vec<String> {
+    let graph = Analyzer::new(config)
+      .analyze_source("lib.rs", source.into())
+      .unwrap();
+
+    let node = graph.nodes.iter().find(|node| node.name == name).unwrap();
+
+    node
+      .edges
+      .iter()
+      .filter(|edge| edge.kind == EdgeKind::Uses)
+      .map(|edge| graph.nodes[edge.target].name.clone())
+      .collect()
+  }
+
+  #[test]
+  fn respect_visibility() {
+    let source = "
+      mod a {
+        fn hidden() {}
+
+        pub fn shown() {}
+      }
+
+      mod b {
+        fn run() {
+          hidden();
+          shown();
+        }
+      }
+      ";
+
+    assert_eq!(uses(source, "run"), ["hidden", "shown"]);
+
+    assert_eq!(
+      uses_with(
+        &Config {
+          respect_visibility: true,
+          ..Config::default()
+        },
+        source,
+        "run",
+      ),
+      ["shown"],
+    );
+  }
+
+  #[test]
+  fn local_shadows_item() {
+    assert!(uses(
+      "
+      struct Config;
+
+      fn run() -> u32 {
+        let Config = 1;
+        Config
+      }
+      ",
+      "run",
+    )
+    .is_empty());
+  }
+
+  #[test]
+  fn closure_parameter_shadows_item() {
+    assert!(uses(
+      "
+      struct Config;
+
+      fn run() -> u32 {
+        let f = |Config: u32| Config;
+        f(1)
+      }
+      ",
+      "run",
+    )
+    .is_empty());
+  }
+
+  #[test]
+  fn shadowing_ends_with_scope() {
+    assert_eq!(
+      uses(
+        "
+        struct Config;
+
+        fn run() {
+          {
+            let Config = 1;
+          }
+          Config;
+        }
+        ",
+        "run",
+      ),
+      ["Config"],
+    );
+  }
+
+  #[test]
+  fn self_path_names_impl_item() {
+    assert_eq!(
+      uses(
+        "
+        struct Config;
+
+        impl Config {
+          fn new() -> Config {
+            Self::helper()
+          }
+
+          fn helper() -> Config {
+            Config
+          }
+        }
+        ",
+        "new",
+      ),
+      ["Config", "helper"],
+    );
+  }
+
+  #[test]
+  fn self_path_names_item_of_other_impl() {
+    assert_eq!(
+      uses(
+        "
+        struct Config;
+
+        impl Config {
+          fn helper() -> Config {
+            Config
+          }
+        }
+
+        impl Default for Config {
+          fn default() -> Config {
+            Self::helper()
+          }
+        }
+        ",
+        "default",
+      ),
+      ["Config", "helper"],
+    );
+  }
+
+  #[test]
+  fn bare_self_names_self_type() {
+    assert_eq!(
+      uses(
+        "
+        struct Config;
+
+        impl Config {
+          fn new() -> Self {
+            Self
+          }
+        }
+        ",
+        "new",
+      ),
+      ["Config"],
+    );
+  }
+
+  #[test]
+  fn self_receiver_names_method_and_self_type() {
+    assert_eq!(
+      uses(
+        "
+        struct Counter {
+          count: u32,
+        }
+
+        impl Counter {
+          fn next(&mut self) -> u32 {
+            self.bump();
+            self.count
+          }
+
+          fn bump(&mut self) {}
+        }
+        ",
+        "next",
+      ),
+      ["bump", "Counter"],
+    );
+  }
+}
@@ -0,0 +1,10 @@
+use super::*;
+
+/// A dependency from one node on another.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+  pub target: NodeId,
+  pub kind: EdgeKind,
+  pub scope: EdgeScope,
+}
@@ -0,0 +1,22 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeKind {
+  /// The source refers to the target by path.
+  Uses,
+  /// The source derives the target, an in-crate derive macro.
+  Derives,
+  /// The source's generics are bounded by the target, an in-crate trait.
+  Bound,
+  /// The source, an in-crate type, implements the target, an in-crate trait.
+  Implements,
+  /// The source, an impl block, is for the target, an in-crate type.
+  SelfType,
+  /// The source package depends on the target, another workspace member.
+  Dependency,
+  /// The source package has a dev-dependency on the target.
+  DevDependency,
+  /// The source package has a build-dependency on the target.
+  BuildDependency,
+}
@@ -0,0 +1,14 @@
+use super::*;
+
+/// Which architectural boundaries an edge crosses.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeScope {
+  /// The source and target are in the same module.
+  #[default]
+  IntraModule,
+  /// The source and target are in different modules of the same package.
+  InterModule,
+  /// The source and target are in different packages.
+  InterPackage,
+}
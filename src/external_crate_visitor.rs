@@ -0,0 +1,64 @@
+use super::*;
+
+/// Collects the names of dependencies that an item's paths, including those
+/// in `use` declarations, start with.
+pub(crate) struct ExternalCrateVisitor<'a> {
+  crates: BTreeSet<String>,
+  dependencies: &'a HashSet<String>,
+}
+
+impl<'a> ExternalCrateVisitor<'a> {
+  pub(crate) fn visit(
+    item: &Item,
+    dependencies: &'a HashSet<String>,
+  ) -> BTreeSet<String> {
+    let mut visitor = Self {
+      crates: BTreeSet::new(),
+      dependencies,
+    };
+
+    visitor.visit_item(item);
+
+    visitor.crates
+  }
+
+  fn add(&mut self, ident: &Ident) {
+    let name = ident.unraw().to_string();
+
+    if self.dependencies.contains(&name) {
+      self.crates.insert(name);
+    }
+  }
+
+  fn visit_use_root(&mut self, tree: &UseTree) {
+    match tree {
+      UseTree::Path(path) => self.add(&path.ident),
+      UseTree::Name(name) => self.add(&name.ident),
+      UseTree::Rename(rename) => self.add(&rename.ident),
+      UseTree::Group(group) => {
+        for tree in &group.items {
+          self.visit_use_root(tree);
+        }
+      }
+      UseTree::Glob(_) => {}
+    }
+  }
+}
+
+impl<'ast> Visit<'ast> for ExternalCrateVisitor<'_> {
+  fn visit_path(&mut self, path: &'ast syn::Path) {
+    if let Some(segment) = path.segments.first() {
+      self.add(&segment.ident);
+    }
+
+    syn::visit::visit_path(self, path);
+  }
+
+  fn visit_item_use(&mut self, i: &'ast ItemUse) {
+    self.visit_use_root(&i.tree);
+  }
+
+  fn visit_item_extern_crate(&mut self, i: &'ast syn::ItemExternCrate) {
+    self.add(&i.ident);
+  }
+}
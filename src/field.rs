@@ -0,0 +1,11 @@
+use super::*;
+
+/// A named and typed value, used for struct fields and function arguments.
+/// Tuple struct fields are named by their position, like `0`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+  pub name: String,
+  #[serde(rename = "typeName")]
+  pub type_name: String,
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+/// A serialization format. Struct fields are written in declaration order,
+/// and maps, such as a graph's `reexports`, are sorted by key, so the same
+/// graph always serializes the same way. `Matrix` is a summary of a whole
+/// graph, rather than a serialization, so it can only be exported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum Format {
+  #[default]
+  Json,
+  JsonPretty,
+  /// CSV with a row and a column per module, where each cell counts the
+  /// dependency edges from the row's module to the column's
+  Matrix,
+  Msgpack,
+}
+
+impl Format {
+  pub(crate) fn content_type(self) -> &'static str {
+    match self {
+      Self::Json | Self::JsonPretty => "application/json",
+      Self::Matrix => "text/csv",
+      Self::Msgpack => "application/msgpack",
+    }
+  }
+
+  /// The format requested by an `Accept` header, defaulting to JSON.
+  pub(crate) fn negotiate(headers: &HeaderMap) -> Self {
+    let accepts_msgpack = headers
+      .get(ACCEPT)
+      .and_then(|accept| accept.to_str().ok())
+      .is_some_and(|accept| {
+        accept.split(',').any(|media_type| {
+          matches!(
+            media_type.split(';').next().unwrap_or_default().trim(),
+            "application/msgpack" | "application/x-msgpack"
+          )
+        })
+      });
+
+    if accepts_msgpack {
+      Self::Msgpack
+    } else {
+      Self::Json
+    }
+  }
+
+  pub(crate) fn serialize(self, value: &impl Serialize) -> Result<Vec<u8>> {
+    Ok(match self {
+      Self::Json => serde_json::to_vec(value)?,
+      Self::JsonPretty => Self::pretty(value, 2)?,
+      Self::Matrix => bail!("the matrix format can only be exported"),
+      Self::Msgpack => rmp_serde::to_vec_named(value)?,
+    })
+  }
+
+  /// A CSV module dependency matrix of `graph`, as described by
+  /// [`Graph::module_matrix`], with module paths as row and column headers.
+  pub(crate) fn matrix(graph: &Graph) -> Vec<u8> {
+    let (modules, matrix) = graph.module_matrix();
+
+    let mut csv = String::new();
+
+    csv.push_str("module");
+
+    for module in &modules {
+      csv.push(',');
+      csv.push_str(module);
+    }
+
+    csv.push('\n');
+
+    for (module, row) in modules.iter().zip(matrix) {
+      csv.push_str(module);
+
+      for count in row {
+        csv.push_str(&format!(",{count}"));
+      }
+
+      csv.push('\n');
+    }
+
+    csv.into_bytes()
+  }
+
+  /// Indented JSON, with `indent` spaces per level and a trailing newline.
+  pub(crate) fn pretty(
+    value: &impl Serialize,
+    indent: usize,
+  ) -> Result<Vec<u8>> {
+    let indent = " ".repeat(indent);
+
+    let mut body = Vec::new();
+
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+      &mut body,
+      PrettyFormatter::with_indent(indent.as_bytes()),
+    ))?;
+
+    body.push(b'\n');
+
+    Ok(body)
+  }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+/// What a function is for, judged from its attributes and the target it's
+/// in, so that tests and benchmarks can be told apart from the code they
+/// exercise.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FunctionRole {
+  #[default]
+  Normal,
+  /// A `#[test]` function, or one with a test attribute from another crate,
+  /// such as `#[tokio::test]`.
+  Test,
+  /// A `#[bench]` function.
+  Bench,
+  /// The `main` function of an example target.
+  ExampleMain,
+}
+
+impl FunctionRole {
+  /// The role that a function's attributes give it.
+  pub(crate) fn from_attributes(attrs: &[Attribute]) -> Self {
+    let is = |name: &str| {
+      attrs.iter().any(|attr| {
+        attr
+          .path
+          .segments
+          .last()
+          .is_some_and(|segment| segment.ident == name)
+      })
+    };
+
+    if is("test") {
+      Self::Test
+    } else if is("bench") {
+      Self::Bench
+    } else {
+      Self::Normal
+    }
+  }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+/// Line ranges, inclusive and keyed by absolute file path, that differ in
+/// the working tree under `dir` from the git revision `since`. Untracked
+/// files are changed in their entirety.
+pub(crate) fn changed_lines(
+  dir: &Path,
+  since: &str,
+) -> Result<HashMap<PathBuf, Vec<(usize, usize)>>> {
+  let dir = dir.canonicalize()?;
+
+  // Check first, since `git diff` outside a repository prints its usage.
+  check_repository(&dir)?;
+
+  let mut changes = HashMap::<PathBuf, Vec<(usize, usize)>>::new();
+
+  let diff = git(
+    &dir,
+    &[
+      "diff",
+      "--unified=0",
+      "--no-color",
+      "--no-prefix",
+      "--relative",
+      since,
+      "--",
+    ],
+  )?;
+
+  let mut file = None;
+
+  for line in diff.lines() {
+    if let Some(path) = line.strip_prefix("+++ ") {
+      file = (path != "/dev/null").then(|| dir.join(path));
+    } else if let (Some(file), Some(hunk)) = (&file, line.strip_prefix("@@ ")) {
+      // A hunk header reads `@@ -old,count +new,count @@`, where a missing
+      // count is one and a count of zero marks a deletion after `new`.
+      let Some(new) = hunk
+        .split_whitespace()
+        .find_map(|range| range.strip_prefix('+'))
+      else {
+        continue;
+      };
+
+      let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse()?, count.parse::<usize>()?),
+        None => (new.parse()?, 1),
+      };
+
+      changes
+        .entry(file.clone())
+        .or_default()
+        .push((start, start + count.saturating_sub(1)));
+    }
+  }
+
+  for path in
+    git(&dir, &["ls-files", "--others", "--exclude-standard"])?.lines()
+  {
+    changes
+      .entry(dir.join(path))
+      .or_default()
+      .push((1, usize::MAX));
+  }
+
+  Ok(changes)
+}
+
+/// Fail unless `dir` is inside a git working tree.
+pub(crate) fn check_repository(dir: &Path) -> Result {
+  git(dir, &["rev-parse", "--is-inside-work-tree"])?;
+  Ok(())
+}
+
+/// Number of commits that touched lines `start` through `end` of `file`.
+pub(crate) fn churn(file: &Path, start: usize, end: usize) -> Result<u32> {
+  let dir = file
+    .parent()
+    .ok_or_else(|| anyhow!("`{}` has no parent directory", file.display()))?;
+
+  let log = git(
+    dir,
+    &[
+      "log",
+      "--format=%H",
+      "--no-patch",
+      &format!("-L{start},{end}:{}", file.display()),
+    ],
+  )?;
+
+  // Older versions of git print patches despite `--no-patch`, but patch
+  // lines are prefixed, so only commit hashes are bare hex.
+  Ok(
+    log
+      .lines()
+      .filter(|line| {
+        line.len() == 40 && line.chars().all(|c| c.is_ascii_hexdigit())
+      })
+      .count()
+      .try_into()?,
+  )
+}
+
+fn git(dir: &Path, arguments: &[&str]) -> Result<String> {
+  let output = Command::new("git")
+    .arg("-C")
+    .arg(dir)
+    .args(arguments)
+    .output()
+    .map_err(|error| anyhow!("failed to run git: {error}"))?;
+
+  if !output.status.success() {
+    bail!(
+      "git {} failed: {}",
+      arguments[0],
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+
+  Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,1473 @@
+use super::*;
+
+/// The result of analyzing a crate: a flat list of nodes, where each node
+/// refers to its children by index.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Graph {
+  /// The version of the serialized format, [`Graph::SCHEMA_VERSION`] for
+  /// graphs produced by this version of eye, and zero for graphs serialized
+  /// before the format was versioned.
+  #[serde(default)]
+  pub schema_version: u32,
+  /// Whether the source was analyzed as expanded by `cargo expand`, per
+  /// [`Config::expand`], rather than as written.
+  #[serde(default)]
+  pub expanded: bool,
+  /// The id of the top-level node, either a workspace or a package.
+  pub root: NodeId,
+  /// Every node in the graph, where a node's id is its index.
+  pub nodes: Vec<Node>,
+  /// Problems encountered during analysis that didn't prevent it from
+  /// finishing.
+  pub warnings: Vec<Warning>,
+  /// Maps the canonical path of each item reachable from outside its crate to
+  /// every public path that names it, following `pub use` re-exports.
+  pub reexports: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Graph {
+  fn default() -> Self {
+    Self {
+      schema_version: Self::SCHEMA_VERSION,
+      expanded: false,
+      root: 0,
+      nodes: Vec::new(),
+      warnings: Vec::new(),
+      reexports: BTreeMap::new(),
+    }
+  }
+}
+
+impl Graph {
+  /// The version of the serialized format, bumped whenever a change could
+  /// break clients, such as removing or renaming a field or changing its
+  /// meaning. Adding fields doesn't bump it.
+  ///
+  /// - 1: The first versioned format.
+  pub const SCHEMA_VERSION: u32 = 1;
+
+  pub(crate) fn sort(&mut self, sort: Sort) {
+    if sort == Sort::Source {
+      return;
+    }
+
+    for i in 0..self.nodes.len() {
+      let mut children = take(&mut self.nodes[i].children);
+
+      match sort {
+        Sort::Source => {}
+        Sort::Name => {
+          children.sort_by(|&a, &b| self.nodes[a].name.cmp(&self.nodes[b].name))
+        }
+        Sort::Kind => {
+          children.sort_by_key(|&child| self.nodes[child].kind.discriminant())
+        }
+      }
+
+      self.nodes[i].children = children;
+    }
+  }
+
+  /// Remove every node for which `keep` returns false, other than the root.
+  /// A removed node's children take its place in its parent's children,
+  /// edges to removed nodes are dropped, and the remaining nodes are
+  /// renumbered.
+  pub fn retain(&mut self, keep: impl Fn(&Node) -> bool) {
+    let keep = self
+      .nodes
+      .iter()
+      .map(|node| node.id == self.root || keep(node))
+      .collect::<Vec<bool>>();
+
+    if keep.iter().all(|&keep| keep) {
+      return;
+    }
+
+    let mut ids = vec![None; self.nodes.len()];
+
+    for (new, old) in (0..self.nodes.len()).filter(|&id| keep[id]).enumerate() {
+      ids[old] = Some(new);
+    }
+
+    let children = self
+      .nodes
+      .iter()
+      .map(|node| {
+        let mut children = Vec::new();
+        self.retained_children(node.id, &keep, &mut children);
+        children
+      })
+      .collect::<Vec<Vec<NodeId>>>();
+
+    self.nodes = take(&mut self.nodes)
+      .into_iter()
+      .zip(children)
+      .filter(|(node, _)| keep[node.id])
+      .map(|(mut node, children)| {
+        node.id = ids[node.id].unwrap();
+        node.children = children
+          .into_iter()
+          .map(|child| ids[child].unwrap())
+          .collect();
+        node.edges = node
+          .edges
+          .into_iter()
+          .filter_map(|edge| {
+            Some(Edge {
+              target: ids[edge.target]?,
+              ..edge
+            })
+          })
+          .collect();
+        node
+      })
+      .collect();
+
+    self.root = ids[self.root].unwrap();
+
+    self.lay_out();
+  }
+
+  /// Set each node's depth and index among its siblings, for clients laying
+  /// out the containment tree.
+  pub(crate) fn lay_out(&mut self) {
+    let mut stack = vec![(self.root, 0, 0)];
+
+    while let Some((id, depth, sibling_index)) = stack.pop() {
+      let node = &mut self.nodes[id];
+
+      node.depth = depth;
+      node.sibling_index = sibling_index;
+
+      stack.extend(
+        node
+          .children
+          .iter()
+          .enumerate()
+          .map(|(index, &child)| (child, depth + 1, index)),
+      );
+    }
+  }
+
+  /// Make every module a direct child of its package, named by its full
+  /// path with `.` separators, such as `eye.subcommand.server`. Target nodes
+  /// are dropped, as are `mod` declarations of file modules, with edges to
+  /// them redirected to the file module.
+  pub(crate) fn flatten(&mut self) {
+    let paths = self.paths();
+
+    let parents = self.parents();
+
+    let is_module = |node: &Node| matches!(node.kind, NodeKind::Module { .. });
+
+    let is_file_module = |id: NodeId| {
+      is_module(&self.nodes[id])
+        && parents[id].is_some_and(|parent| {
+          matches!(
+            self.nodes[parent].kind,
+            NodeKind::Package { .. } | NodeKind::Target { .. }
+          )
+        })
+    };
+
+    let mut file_modules = HashMap::new();
+
+    for (id, path) in paths.iter().enumerate() {
+      if let Some(path) = path.as_ref().filter(|_| is_file_module(id)) {
+        file_modules.entry(path).or_insert(id);
+      }
+    }
+
+    let redirects = (0..self.nodes.len())
+      .map(|id| {
+        paths[id]
+          .as_ref()
+          .filter(|_| is_module(&self.nodes[id]) && !is_file_module(id))
+          .and_then(|path| file_modules.get(path).copied())
+      })
+      .collect::<Vec<Option<NodeId>>>();
+
+    for node in &mut self.nodes {
+      if is_module(node) {
+        if let Some(path) = &paths[node.id] {
+          node.name = path.replace("::", ".");
+        }
+      }
+
+      for edge in &mut node.edges {
+        edge.target = redirects[edge.target].unwrap_or(edge.target);
+      }
+    }
+
+    self.retain(|node| {
+      redirects[node.id].is_none()
+        && !matches!(node.kind, NodeKind::Target { .. })
+    });
+
+    let parents = self.parents();
+
+    let packages =
+      self.nearest(|kind| matches!(kind, NodeKind::Package { .. }));
+
+    for id in 0..self.nodes.len() {
+      if !is_module(&self.nodes[id]) {
+        continue;
+      }
+
+      if let (Some(parent), Some(package)) = (parents[id], packages[id]) {
+        if parent != package {
+          self.nodes[parent].children.retain(|&child| child != id);
+          self.nodes[package].children.push(id);
+        }
+      }
+    }
+  }
+
+  /// Keep only the nodes that match, along with their ancestors, so that
+  /// they're still reachable from the root.
+  pub(crate) fn retain_matching(&mut self, matches: impl Fn(&Node) -> bool) {
+    let mut keep = vec![false; self.nodes.len()];
+
+    // Children come after their parents, so visiting nodes in reverse
+    // settles whether a node has a kept descendant before reaching it.
+    for node in self.nodes.iter().rev() {
+      keep[node.id] =
+        matches(node) || node.children.iter().any(|&child| keep[child]);
+    }
+
+    self.retain(|node| keep[node.id]);
+  }
+
+  /// Apply the settings of `config` that only drop or rearrange nodes and
+  /// edges, and so apply as well to a finished graph, like an exported
+  /// snapshot, as during analysis: `flatten`, `exclude_kinds`,
+  /// `name_filter`, `only_changed`, `reachable_only`, `visibility`, and
+  /// `no_deps_edges`, which drops `uses` edges.
+  pub fn filter(&mut self, config: &Config) {
+    if config.flatten {
+      self.flatten();
+    }
+
+    if !config.exclude_kinds.is_empty() {
+      self.retain(|node| {
+        !config
+          .exclude_kinds
+          .iter()
+          .any(|kind| kind == node.kind.name())
+      });
+    }
+
+    if let Some(name_filter) = &config.name_filter {
+      self.retain_matching(|node| name_filter.is_match(&node.name));
+    }
+
+    if config.only_changed {
+      self.retain_matching(|node| node.changed);
+    }
+
+    if config.reachable_only {
+      self.retain_matching(|node| node.publicly_reachable);
+    }
+
+    if let Some(visibility) = config.visibility {
+      self.retain_matching(|node| {
+        node.kind.namespace().is_some() && node.visibility <= visibility
+      });
+    }
+
+    if config.no_deps_edges {
+      for node in &mut self.nodes {
+        node.edges.retain(|edge| edge.kind != EdgeKind::Uses);
+      }
+    }
+  }
+
+  /// Renumber nodes in `order` from the root, so that parents come before
+  /// their children, dropping any that aren't reachable.
+  pub(crate) fn renumber(&mut self, order: IdOrder) {
+    let mut visited = Vec::new();
+
+    let mut pending = VecDeque::from([self.root]);
+
+    while let Some(id) = match order {
+      IdOrder::Dfs => pending.pop_back(),
+      IdOrder::Bfs => pending.pop_front(),
+    } {
+      visited.push(id);
+
+      match order {
+        IdOrder::Dfs => pending.extend(self.nodes[id].children.iter().rev()),
+        IdOrder::Bfs => pending.extend(&self.nodes[id].children),
+      }
+    }
+
+    let order = visited;
+
+    let mut ids = vec![None; self.nodes.len()];
+
+    for (new, &old) in order.iter().enumerate() {
+      ids[old] = Some(new);
+    }
+
+    let mut nodes = take(&mut self.nodes)
+      .into_iter()
+      .map(Some)
+      .collect::<Vec<Option<Node>>>();
+
+    self.nodes = order
+      .into_iter()
+      .map(|old| {
+        let mut node = nodes[old].take().unwrap();
+        node.id = ids[old].unwrap();
+        node.children = node
+          .children
+          .iter()
+          .map(|&child| ids[child].unwrap())
+          .collect();
+        node.edges = node
+          .edges
+          .into_iter()
+          .filter_map(|edge| {
+            Some(Edge {
+              target: ids[edge.target]?,
+              ..edge
+            })
+          })
+          .collect();
+        node
+      })
+      .collect();
+
+    self.root = 0;
+  }
+
+  /// Renumber nodes to keep the ids they had in `previous`, an earlier
+  /// analysis of the same crate, so that a client holding an id still finds
+  /// the same item. A node keeps its id if its parent did and `previous` had
+  /// a child of that parent with the same kind and name, matched in order
+  /// among children sharing both.
+  ///
+  /// The ids of nodes that this analysis removed are held by
+  /// [`NodeKind::Removed`] placeholders, so that they aren't reused right
+  /// away. Placeholders that `previous` already had are free: new nodes take
+  /// the lowest free id past their parent's, so that parents still come
+  /// before their children, or else an id past every node `previous` kept,
+  /// and free ids that aren't taken are dropped from the end of the graph.
+  pub fn preserve_ids(&mut self, previous: &Graph) {
+    let mut ids = vec![None; self.nodes.len()];
+
+    let key = |node: &Node| (node.kind.name(), node.name.clone());
+
+    let mut pending = Vec::new();
+
+    if previous
+      .nodes
+      .get(previous.root)
+      .is_some_and(|root| key(root) == key(&self.nodes[self.root]))
+    {
+      pending.push((self.root, previous.root));
+    }
+
+    let mut matched = vec![false; previous.nodes.len()];
+
+    while let Some((id, old)) = pending.pop() {
+      ids[id] = Some(old);
+      matched[old] = true;
+
+      let mut candidates = HashMap::<_, VecDeque<NodeId>>::new();
+
+      for &child in &previous.nodes[old].children {
+        candidates
+          .entry(key(&previous.nodes[child]))
+          .or_default()
+          .push_back(child);
+      }
+
+      for &child in &self.nodes[id].children {
+        if let Some(old) = candidates
+          .get_mut(&key(&self.nodes[child]))
+          .and_then(VecDeque::pop_front)
+        {
+          pending.push((child, old));
+        }
+      }
+    }
+
+    let mut free = BTreeSet::new();
+
+    let mut len = 0;
+
+    for node in &previous.nodes {
+      if node.kind == NodeKind::Removed {
+        free.insert(node.id);
+      } else {
+        len = node.id + 1;
+      }
+    }
+
+    let mut next = len;
+
+    let parents = self.parents();
+
+    // Parents come before their children, so a new node's parent already
+    // has its id.
+    for id in 0..ids.len() {
+      if ids[id].is_some() {
+        continue;
+      }
+
+      let after = parents[id].map_or(0, |parent| ids[parent].unwrap() + 1);
+
+      ids[id] = Some(match free.range(after..).next().copied() {
+        Some(free_id) => {
+          free.remove(&free_id);
+          free_id
+        }
+        None => {
+          next += 1;
+          next - 1
+        }
+      });
+    }
+
+    let ids = ids.into_iter().map(Option::unwrap).collect::<Vec<NodeId>>();
+
+    // Nodes that this analysis removed keep their placeholders.
+    let len = matched
+      .iter()
+      .enumerate()
+      .filter(|&(old, &matched)| {
+        !matched && previous.nodes[old].kind != NodeKind::Removed
+      })
+      .map(|(old, _)| old + 1)
+      .chain(ids.iter().map(|&id| id + 1))
+      .max()
+      .unwrap_or(0);
+
+    let mut nodes = (0..len)
+      .map(|id| Node {
+        id,
+        kind: NodeKind::Removed,
+        ..Node::default()
+      })
+      .collect::<Vec<Node>>();
+
+    for mut node in take(&mut self.nodes) {
+      node.id = ids[node.id];
+      node.children = node.children.iter().map(|&child| ids[child]).collect();
+      for edge in &mut node.edges {
+        edge.target = ids[edge.target];
+      }
+      node.duplicate_of = node.duplicate_of.map(|id| ids[id]);
+      let id = node.id;
+      nodes[id] = node;
+    }
+
+    self.nodes = nodes;
+    self.root = ids[self.root];
+  }
+
+  fn retained_children(
+    &self,
+    id: NodeId,
+    keep: &[bool],
+    children: &mut Vec<NodeId>,
+  ) {
+    for &child in &self.nodes[id].children {
+      if keep[child] {
+        children.push(child);
+      } else {
+        self.retained_children(child, keep, children);
+      }
+    }
+  }
+
+  /// The subgraph rooted at `id`: that node and its containment descendants,
+  /// renumbered from zero. Edges leaving the subgraph point to `External`
+  /// stand-ins, which come after the subgraph's own nodes.
+  pub fn subtree(&self, id: NodeId) -> Option<Graph> {
+    self.nodes.get(id)?;
+
+    let mut order = vec![id];
+
+    let mut i = 0;
+
+    while let Some(&id) = order.get(i) {
+      order.extend(&self.nodes[id].children);
+      i += 1;
+    }
+
+    let mut ids = HashMap::new();
+
+    for (new, &old) in order.iter().enumerate() {
+      ids.insert(old, new);
+    }
+
+    let mut nodes = Vec::new();
+
+    let mut externals = Vec::new();
+
+    for &old in &order {
+      let mut node = self.nodes[old].clone();
+
+      node.id = ids[&old];
+
+      node.children = node.children.iter().map(|child| ids[child]).collect();
+
+      for edge in &mut node.edges {
+        edge.target = match ids.get(&edge.target) {
+          Some(&target) => target,
+          None => {
+            let target = order.len() + externals.len();
+            ids.insert(edge.target, target);
+            externals.push(edge.target);
+            target
+          }
+        };
+      }
+
+      nodes.push(node);
+    }
+
+    for (i, old) in externals.into_iter().enumerate() {
+      let node = &self.nodes[old];
+
+      nodes.push(Node {
+        id: order.len() + i,
+        name: node.name.clone(),
+        kind: NodeKind::External { id: old },
+        visibility: node.visibility,
+        ..Default::default()
+      });
+    }
+
+    let mut subtree = Graph {
+      root: 0,
+      nodes,
+      ..Default::default()
+    };
+
+    subtree.lay_out();
+
+    Some(subtree)
+  }
+
+  /// Make the file paths of nodes and warnings relative to `root`, with `/`
+  /// separators, so that graphs don't depend on where the crate lives.
+  /// Paths outside `root` are left as they are.
+  pub(crate) fn relativize(&mut self, root: &Path) {
+    let relative = |path: &mut PathBuf| {
+      if let Ok(relative) = path.strip_prefix(root) {
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        *path = PathBuf::from(if relative.is_empty() {
+          ".".into()
+        } else {
+          relative
+        });
+      }
+    };
+
+    for node in &mut self.nodes {
+      match &mut node.kind {
+        NodeKind::Workspace { path }
+        | NodeKind::Package { path, .. }
+        | NodeKind::Target { path, .. }
+        | NodeKind::Module { path, .. } => relative(path),
+        _ => {}
+      }
+    }
+
+    for warning in &mut self.warnings {
+      relative(&mut warning.path);
+    }
+  }
+
+  /// Replace matches of any of `patterns` in each node's source code,
+  /// documentation, and documentation examples with `[REDACTED]`.
+  pub(crate) fn redact(&mut self, patterns: &[Regex]) {
+    let redact = |text: &mut String| {
+      for pattern in patterns {
+        if let Cow::Owned(redacted) = pattern.replace_all(text, "[REDACTED]") {
+          *text = redacted;
+        }
+      }
+    };
+
+    for node in &mut self.nodes {
+      redact(&mut node.source_code);
+      redact(&mut node.documentation);
+
+      for example in &mut node.doc_examples {
+        redact(example);
+      }
+    }
+  }
+
+  /// The parent of each node in the containment hierarchy.
+  pub(crate) fn parents(&self) -> Vec<Option<NodeId>> {
+    let mut parents = vec![None; self.nodes.len()];
+
+    for node in &self.nodes {
+      for &child in &node.children {
+        parents[child] = Some(node.id);
+      }
+    }
+
+    parents
+  }
+
+  /// The canonical path of every module, sorted, and, for each pair of
+  /// modules, the number of edges from items in the first to items in the
+  /// second, in the same order, for gauging how coupled modules are. A `mod`
+  /// declaration and the file module it names share a path, so they're
+  /// counted as one module. Edges between packages aren't counted.
+  pub fn module_matrix(&self) -> (Vec<String>, Vec<Vec<usize>>) {
+    let paths = self.paths();
+
+    let modules = self.nearest(|kind| matches!(kind, NodeKind::Module { .. }));
+
+    let module_paths = modules
+      .iter()
+      .flatten()
+      .filter_map(|&module| paths[module].clone())
+      .collect::<BTreeSet<String>>()
+      .into_iter()
+      .collect::<Vec<String>>();
+
+    let index = |id: NodeId| {
+      let path = paths[modules[id]?].as_ref()?;
+      module_paths.binary_search(path).ok()
+    };
+
+    let mut matrix = vec![vec![0; module_paths.len()]; module_paths.len()];
+
+    for node in &self.nodes {
+      let Some(source) = index(node.id) else {
+        continue;
+      };
+
+      for edge in &node.edges {
+        if let Some(target) = index(edge.target) {
+          matrix[source][target] += 1;
+        }
+      }
+    }
+
+    (module_paths, matrix)
+  }
+
+  /// Whether each node is hidden from documentation, because it or one of
+  /// its ancestors is marked `#[doc(hidden)]`.
+  pub fn hidden(&self) -> Vec<bool> {
+    let parents = self.parents();
+
+    let mut hidden = vec![false; self.nodes.len()];
+
+    // Parents are always pushed before their children.
+    for node in &self.nodes {
+      hidden[node.id] = node.doc_hidden
+        || parents[node.id].is_some_and(|parent| hidden[parent]);
+    }
+
+    hidden
+  }
+
+  /// The methods of the in-crate type `id` from the crate's impl blocks,
+  /// including the defaults of in-crate traits that it inherits rather than
+  /// overriding, like rustdoc's provided methods.
+  pub fn methods(&self, id: NodeId) -> Vec<Method> {
+    let traits = self.nodes[id]
+      .edges
+      .iter()
+      .filter(|edge| edge.kind == EdgeKind::Implements)
+      .map(|edge| edge.target)
+      .collect::<Vec<NodeId>>();
+
+    let mut methods = Vec::new();
+
+    for node in &self.nodes {
+      let NodeKind::Impl { trait_name, .. } = &node.kind else {
+        continue;
+      };
+
+      if !node
+        .edges
+        .iter()
+        .any(|edge| edge.target == id && edge.kind == EdgeKind::SelfType)
+      {
+        continue;
+      }
+
+      let trait_id = trait_name.as_ref().and_then(|trait_name| {
+        let name = trait_name
+          .split('<')
+          .next()
+          .and_then(|path| path.rsplit("::").next())
+          .unwrap_or_default();
+
+        traits
+          .iter()
+          .copied()
+          .find(|&trait_id| self.nodes[trait_id].name == name)
+      });
+
+      let names = node
+        .children
+        .iter()
+        .map(|&child| &self.nodes[child])
+        .filter(|child| matches!(child.kind, NodeKind::Function { .. }))
+        .map(|child| child.name.clone())
+        .collect::<Vec<String>>();
+
+      let inherited = match trait_id.map(|trait_id| &self.nodes[trait_id].kind)
+      {
+        Some(NodeKind::Trait {
+          provided_methods, ..
+        }) => provided_methods
+          .iter()
+          .filter(|provided| !names.contains(provided))
+          .cloned()
+          .collect(),
+        _ => Vec::new(),
+      };
+
+      methods.extend(
+        names
+          .into_iter()
+          .map(|name| (name, false))
+          .chain(inherited.into_iter().map(|name| (name, true)))
+          .map(|(name, inherited)| Method {
+            name,
+            trait_name: trait_name.clone(),
+            trait_id,
+            inherited,
+          }),
+      );
+    }
+
+    methods
+  }
+
+  /// The canonical path of each module and item, such as `eye::graph::Graph`,
+  /// derived from its package's name, its file's path relative to the source
+  /// directory, and any enclosing inline modules.
+  pub fn paths(&self) -> Vec<Option<String>> {
+    let parents = self.parents();
+
+    let mut paths = vec![None; self.nodes.len()];
+
+    // The path that children are named under, which for a macro invocation
+    // is that of its enclosing module, since expanded items belong there.
+    let mut namespaces = vec![None; self.nodes.len()];
+
+    // Parents are always pushed before their children.
+    for node in &self.nodes {
+      let Some(parent) = parents[node.id].map(|parent| &self.nodes[parent])
+      else {
+        // A single analyzed file is a crate root with no package.
+        if matches!(node.kind, NodeKind::Module { .. }) {
+          paths[node.id] = Some("crate".to_owned());
+          namespaces[node.id] = paths[node.id].clone();
+        }
+
+        continue;
+      };
+
+      paths[node.id] = match (&node.kind, &parent.kind) {
+        // Implementations have no name to refer to them, or their items, by.
+        (NodeKind::Impl { .. }, _) => None,
+        (
+          NodeKind::Module { path, .. },
+          NodeKind::Target { path: root, .. },
+        ) if path == root => Some(parent.name.replace('-', "_")),
+        (
+          NodeKind::Module { .. },
+          NodeKind::Package { .. } | NodeKind::Target { .. },
+        ) => Some(Self::module_path(&parent.name, &node.name)),
+        _ => namespaces[parent.id]
+          .as_ref()
+          .map(|path| format!("{path}::{}", node.name)),
+      };
+
+      namespaces[node.id] = match node.kind {
+        NodeKind::Macro { .. } => namespaces[parent.id].clone(),
+        _ => paths[node.id].clone(),
+      };
+    }
+
+    paths
+  }
+
+  /// Give each file module the visibility of the `mod` declaration that
+  /// names it, treating crate roots as public, and mark it doc-hidden if the
+  /// declaration is.
+  pub(crate) fn inherit_module_visibility(&mut self) {
+    let paths = self.paths();
+
+    let parents = self.parents();
+
+    for id in 0..self.nodes.len() {
+      let is_file_module =
+        matches!(self.nodes[id].kind, NodeKind::Module { .. })
+          && parents[id].is_none_or(|parent| {
+            matches!(
+              self.nodes[parent].kind,
+              NodeKind::Package { .. } | NodeKind::Target { .. }
+            )
+          });
+
+      let Some(path) = paths[id].as_ref().filter(|_| is_file_module) else {
+        continue;
+      };
+
+      let declaration = (0..self.nodes.len()).find(|&other| {
+        other != id
+          && paths[other].as_ref() == Some(path)
+          && matches!(self.nodes[other].kind, NodeKind::Module { .. })
+      });
+
+      if let Some(declaration) = declaration {
+        self.nodes[id].doc_hidden |= self.nodes[declaration].doc_hidden;
+      }
+
+      self.nodes[id].visibility = match declaration {
+        Some(declaration) => self.nodes[declaration].visibility,
+        None if !path.contains("::") => Visibility::Public,
+        None => continue,
+      };
+    }
+  }
+
+  /// The canonical path of the module in `file`, relative to the source
+  /// directory of `krate`.
+  fn module_path(krate: &str, file: &str) -> String {
+    let file = Path::new(file).with_extension("");
+
+    let mut segments = file
+      .iter()
+      .map(|segment| segment.to_string_lossy().into_owned())
+      .collect::<Vec<String>>();
+
+    if segments.last().is_some_and(|segment| segment == "mod")
+      || segments == ["lib"]
+      || segments == ["main"]
+    {
+      segments.pop();
+    }
+
+    segments.insert(0, krate.replace('-', "_"));
+
+    segments.join("::")
+  }
+
+  /// The nearest ancestor of each node, or the node itself, whose kind
+  /// satisfies `predicate`.
+  pub fn nearest(
+    &self,
+    predicate: fn(&NodeKind) -> bool,
+  ) -> Vec<Option<NodeId>> {
+    let parents = self.parents();
+
+    (0..self.nodes.len())
+      .map(|mut id| loop {
+        if predicate(&self.nodes[id].kind) {
+          return Some(id);
+        }
+
+        id = parents[id]?;
+      })
+      .collect()
+  }
+
+  /// Classify each edge by whether its source and target share a module or
+  /// package, where a node's module and package are its nearest ancestors, or
+  /// itself, of that kind.
+  pub(crate) fn scope_edges(&mut self) {
+    let modules = self.nearest(|kind| matches!(kind, NodeKind::Module { .. }));
+
+    let packages =
+      self.nearest(|kind| matches!(kind, NodeKind::Package { .. }));
+
+    for node in &mut self.nodes {
+      for edge in &mut node.edges {
+        edge.scope = if packages[node.id] != packages[edge.target] {
+          EdgeScope::InterPackage
+        } else if modules[node.id] != modules[edge.target] {
+          EdgeScope::InterModule
+        } else {
+          EdgeScope::IntraModule
+        };
+      }
+    }
+  }
+
+  /// Merge the annotations for each pattern into the `annotations` of every
+  /// node whose canonical path matches it, where `*` matches within a path
+  /// segment and `**` across segments, so `app::*` matches `app::Config` but
+  /// not `app::db::Pool`. Patterns are applied in sorted order, so keys from
+  /// later patterns override those from earlier ones. Patterns that match
+  /// nothing are warned about, concerning `file`.
+  pub(crate) fn annotate(
+    &mut self,
+    annotations: &serde_json::Map<String, serde_json::Value>,
+    file: &Path,
+  ) -> Result {
+    let paths = self.paths();
+
+    for (pattern, value) in annotations {
+      let serde_json::Value::Object(fields) = value else {
+        bail!(
+          "annotations for `{pattern}` in `{}` must be a JSON object",
+          file.display()
+        );
+      };
+
+      let regex = Self::glob(pattern);
+
+      let mut matched = false;
+
+      for (node, path) in self.nodes.iter_mut().zip(&paths) {
+        if path.as_ref().is_some_and(|path| regex.is_match(path)) {
+          matched = true;
+
+          node.annotations.extend(
+            fields
+              .iter()
+              .map(|(key, value)| (key.clone(), value.clone())),
+          );
+        }
+      }
+
+      if !matched {
+        self.warnings.push(Warning {
+          category: WarningCategory::Annotation,
+          message: format!("annotation pattern `{pattern}` matched no items"),
+          path: file.to_path_buf(),
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// A regular expression matching the paths that glob `pattern` does.
+  fn glob(pattern: &str) -> Regex {
+    let mut regex = "^".to_owned();
+
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+      match c {
+        '*' if chars.peek() == Some(&'*') => {
+          chars.next();
+          regex.push_str(".*");
+        }
+        '*' => regex.push_str("[^:]*"),
+        '?' => regex.push_str("[^:]"),
+        c => regex.push_str(&regex::escape(&c.to_string())),
+      }
+    }
+
+    regex.push('$');
+
+    Regex::new(&regex).unwrap()
+  }
+
+  /// Link each item to an earlier item of the same name in the same module
+  /// and namespace, such as one defined under a different `cfg`, and warn
+  /// about it, since references to the name may resolve to either.
+  pub(crate) fn mark_duplicates(&mut self) {
+    let paths = self.paths();
+
+    for (module, path) in paths.iter().enumerate() {
+      let NodeKind::Module { path: file, .. } = &self.nodes[module].kind else {
+        continue;
+      };
+
+      let file = file.clone();
+
+      let mut first = HashMap::<(&str, &str), NodeId>::new();
+
+      let mut duplicates = Vec::new();
+
+      for &child in &self.nodes[module].children {
+        let node = &self.nodes[child];
+
+        let Some(namespace) = node.kind.namespace() else {
+          continue;
+        };
+
+        match first.get(&(namespace, node.name.as_str())) {
+          Some(&original) => duplicates.push((child, original)),
+          None => {
+            first.insert((namespace, &node.name), child);
+          }
+        }
+      }
+
+      for (duplicate, original) in duplicates {
+        self.nodes[duplicate].duplicate_of = Some(original);
+
+        self.warnings.push(Warning {
+          category: WarningCategory::Duplicate,
+          message: format!(
+            "`{}` is defined more than once in `{}`",
+            self.nodes[duplicate].name,
+            path.as_deref().unwrap_or("crate"),
+          ),
+          path: file.clone(),
+        });
+      }
+    }
+  }
+
+  /// Flag the items that code outside their crate can reach. An item with a
+  /// public path, following `pub use` re-exports, is reachable, as is any
+  /// item that a reachable item refers to, by name, in its signature, such
+  /// as the argument and return types of a function or the field types of a
+  /// struct, since it's exposed there even if it's private. Every field of a
+  /// struct counts, whether or not it's public, and bodies never do. The
+  /// items of a reachable type's impl blocks are reachable too: all of those
+  /// of a trait impl, and the `pub` ones of an inherent impl.
+  pub(crate) fn mark_publicly_reachable(&mut self) {
+    let paths = self.paths();
+
+    let mut impls = HashMap::<NodeId, Vec<NodeId>>::new();
+
+    for node in &self.nodes {
+      for edge in &node.edges {
+        if edge.kind == EdgeKind::SelfType {
+          impls.entry(edge.target).or_default().push(node.id);
+        }
+      }
+    }
+
+    let mut queue = (0..self.nodes.len())
+      .filter(|&id| {
+        paths[id]
+          .as_ref()
+          .is_some_and(|path| self.reexports.contains_key(path))
+      })
+      .collect::<VecDeque<NodeId>>();
+
+    for &id in &queue {
+      self.nodes[id].publicly_reachable = true;
+    }
+
+    while let Some(id) = queue.pop_front() {
+      for &impl_id in impls.get(&id).into_iter().flatten() {
+        let is_trait_impl = matches!(
+          self.nodes[impl_id].kind,
+          NodeKind::Impl {
+            trait_name: Some(_),
+            ..
+          }
+        );
+
+        for child in self.nodes[impl_id].children.clone() {
+          let item = &mut self.nodes[child];
+
+          if !item.publicly_reachable
+            && (is_trait_impl || item.visibility == Visibility::Public)
+          {
+            item.publicly_reachable = true;
+            queue.push_back(child);
+          }
+        }
+      }
+
+      let Some(signature) = self.nodes[id].signature() else {
+        continue;
+      };
+
+      let names = signature
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .collect::<HashSet<&str>>();
+
+      for edge in self.nodes[id].edges.clone() {
+        let target = &mut self.nodes[edge.target];
+
+        if edge.kind == EdgeKind::Uses
+          && !target.publicly_reachable
+          && names.contains(target.name.as_str())
+        {
+          target.publicly_reachable = true;
+          queue.push_back(edge.target);
+        }
+      }
+    }
+  }
+
+  /// Rewrite the types of struct fields and function arguments and return
+  /// values to name in-crate types by their canonical paths, such as
+  /// `crate::server::Config` for `Config`. A name is only qualified if the
+  /// node has a `uses` edge to a type or trait by that name, so types outside
+  /// the crate, and any that weren't traced, are left as written. Types in
+  /// other packages of a workspace are named from their package.
+  pub(crate) fn qualify_types(&mut self) {
+    let paths = self.paths();
+
+    let parents = self.parents();
+
+    for id in 0..self.nodes.len() {
+      // Items of impl blocks have no path of their own, but are in the crate
+      // of the nearest ancestor that does.
+      let Some(own_crate) = iter::successors(Some(id), |&id| parents[id])
+        .find_map(|id| paths[id].as_deref())
+        .and_then(|path| path.split("::").next())
+      else {
+        continue;
+      };
+
+      let targets = self.nodes[id]
+        .edges
+        .iter()
+        .filter(|edge| {
+          edge.kind == EdgeKind::Uses
+            && matches!(
+              self.nodes[edge.target].kind,
+              NodeKind::Struct { .. }
+                | NodeKind::Enum { .. }
+                | NodeKind::Union { .. }
+                | NodeKind::Type { .. }
+                | NodeKind::Trait { .. }
+                | NodeKind::TraitAlias { .. }
+            )
+        })
+        .filter_map(|edge| {
+          let path = paths[edge.target].as_deref()?;
+
+          let path = match path.split_once("::") {
+            Some((krate, rest)) if krate == own_crate => {
+              format!("crate::{rest}")
+            }
+            _ => path.to_owned(),
+          };
+
+          Some((self.nodes[edge.target].name.as_str(), path))
+        })
+        .collect::<HashMap<&str, String>>();
+
+      if targets.is_empty() {
+        continue;
+      }
+
+      let qualify =
+        |type_name: &str| TypeQualifier::qualify(&targets, type_name);
+
+      let qualified = match &self.nodes[id].kind {
+        NodeKind::Function {
+          arguments,
+          return_type,
+          ..
+        } => (
+          arguments
+            .iter()
+            .map(|argument| qualify(&argument.type_name))
+            .collect::<Vec<String>>(),
+          return_type.as_deref().map(qualify),
+        ),
+        NodeKind::Struct { fields, .. } => (
+          fields
+            .iter()
+            .map(|field| qualify(&field.type_name))
+            .collect(),
+          None,
+        ),
+        _ => continue,
+      };
+
+      match &mut self.nodes[id].kind {
+        NodeKind::Function {
+          arguments,
+          return_type,
+          ..
+        } => {
+          for (argument, type_name) in arguments.iter_mut().zip(qualified.0) {
+            argument.type_name = type_name;
+          }
+
+          *return_type = qualified.1;
+        }
+        NodeKind::Struct { fields, .. } => {
+          for (field, type_name) in fields.iter_mut().zip(qualified.0) {
+            field.type_name = type_name;
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  /// Flag functions that are part of a cycle of `uses` edges between
+  /// functions, found with Tarjan's strongly connected components algorithm,
+  /// and types that use themselves.
+  pub(crate) fn mark_recursion(&mut self) {
+    let is_function =
+      |node: &Node| matches!(node.kind, NodeKind::Function { .. });
+
+    let mut tarjan = Tarjan {
+      graph: self,
+      index: 0,
+      indices: vec![None; self.nodes.len()],
+      low: vec![0; self.nodes.len()],
+      stack: Vec::new(),
+      on_stack: vec![false; self.nodes.len()],
+      recursive: vec![false; self.nodes.len()],
+    };
+
+    for id in 0..self.nodes.len() {
+      if is_function(&self.nodes[id]) && tarjan.indices[id].is_none() {
+        tarjan.connect(id);
+      }
+    }
+
+    let recursive = tarjan.recursive;
+
+    for node in &mut self.nodes {
+      let uses_itself = node
+        .edges
+        .iter()
+        .any(|edge| edge.target == node.id && edge.kind == EdgeKind::Uses);
+
+      match &mut node.kind {
+        NodeKind::Function { is_recursive, .. } => {
+          *is_recursive = uses_itself || recursive[node.id];
+        }
+        NodeKind::Struct {
+          self_referential, ..
+        }
+        | NodeKind::Enum {
+          self_referential, ..
+        } => *self_referential = uses_itself,
+        _ => {}
+      }
+    }
+  }
+}
+
+struct Tarjan<'a> {
+  graph: &'a Graph,
+  index: usize,
+  indices: Vec<Option<usize>>,
+  low: Vec<usize>,
+  stack: Vec<NodeId>,
+  on_stack: Vec<bool>,
+  recursive: Vec<bool>,
+}
+
+impl Tarjan<'_> {
+  fn connect(&mut self, id: NodeId) {
+    self.indices[id] = Some(self.index);
+    self.low[id] = self.index;
+    self.index += 1;
+    self.stack.push(id);
+    self.on_stack[id] = true;
+
+    for edge in &self.graph.nodes[id].edges {
+      let target = edge.target;
+
+      if edge.kind != EdgeKind::Uses
+        || !matches!(self.graph.nodes[target].kind, NodeKind::Function { .. })
+      {
+        continue;
+      }
+
+      match self.indices[target] {
+        None => {
+          self.connect(target);
+          self.low[id] = self.low[id].min(self.low[target]);
+        }
+        Some(index) if self.on_stack[target] => {
+          self.low[id] = self.low[id].min(index);
+        }
+        Some(_) => {}
+      }
+    }
+
+    if Some(self.low[id]) == self.indices[id] {
+      let mut component = Vec::new();
+
+      loop {
+        let member = self.stack.pop().unwrap();
+        self.on_stack[member] = false;
+        component.push(member);
+
+        if member == id {
+          break;
+        }
+      }
+
+      if component.len() > 1 {
+        for member in component {
+          self.recursive[member] = true;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn methods() {
+    let graph = Analyzer::new(&Config::default())
+      .analyze_source(
+        "lib.rs",
+        "
+        trait Greet {
+          fn name(&self) -> String;
+
+          fn hello(&self) -> String {
+            self.name()
+          }
+        }
+
+        struct Person;
+
+        impl Person {
+          fn new() -> Self {
+            Self
+          }
+        }
+
+        impl Greet for Person {
+          fn name(&self) -> String {
+            String::new()
+          }
+        }
+        "
+        .into(),
+      )
+      .unwrap();
+
+    let id = |name: &str| {
+      graph
+        .nodes
+        .iter()
+        .position(|node| node.name == name)
+        .unwrap()
+    };
+
+    assert_eq!(
+      graph.methods(id("Person")),
+      [
+        Method {
+          name: "new".into(),
+          trait_name: None,
+          trait_id: None,
+          inherited: false,
+        },
+        Method {
+          name: "name".into(),
+          trait_name: Some("Greet".into()),
+          trait_id: Some(id("Greet")),
+          inherited: false,
+        },
+        Method {
+          name: "hello".into(),
+          trait_name: Some("Greet".into()),
+          trait_id: Some(id("Greet")),
+          inherited: true,
+        },
+      ],
+    );
+  }
+
+  #[test]
+  fn preserve_ids() {
+    let analyze = |source: &str| {
+      Analyzer::new(&Config::default())
+        .analyze_source("lib.rs", source.into())
+        .unwrap()
+    };
+
+    let id = |graph: &Graph, name: &str| {
+      graph
+        .nodes
+        .iter()
+        .position(|node| node.name == name)
+        .unwrap()
+    };
+
+    let first = analyze("fn a() {} fn b() {} fn c() { a() }");
+
+    let mut second = analyze("fn new() {} fn a() {} fn c() { a() }");
+    second.preserve_ids(&first);
+
+    assert_eq!(id(&second, "a"), id(&first, "a"));
+    assert_eq!(id(&second, "c"), id(&first, "c"));
+    assert_eq!(second.nodes[id(&first, "b")].kind, NodeKind::Removed);
+    assert_eq!(id(&second, "new"), first.nodes.len());
+    assert_eq!(
+      second.nodes[id(&second, "c")]
+        .edges
+        .iter()
+        .map(|edge| edge.target)
+        .collect::<Vec<NodeId>>(),
+      [id(&first, "a")],
+    );
+
+    let mut third = analyze("fn a() {} fn c() { a() }");
+    third.preserve_ids(&second);
+
+    assert_eq!(third.nodes.len(), second.nodes.len());
+    assert_eq!(third.nodes[id(&second, "new")].kind, NodeKind::Removed);
+
+    let mut fourth = analyze("fn a() {} fn c() { a() }");
+    fourth.preserve_ids(&third);
+
+    assert_eq!(fourth.nodes.len(), first.nodes.len());
+  }
+
+  #[test]
+  fn preserve_ids_reuses_placeholders() {
+    let analyze = |source: &str, previous: Option<&Graph>| {
+      let mut graph = Analyzer::new(&Config::default())
+        .analyze_source("lib.rs", source.into())
+        .unwrap();
+
+      if let Some(previous) = previous {
+        graph.preserve_ids(previous);
+      }
+
+      graph
+    };
+
+    let id = |graph: &Graph, name: &str| {
+      graph
+        .nodes
+        .iter()
+        .position(|node| node.name == name)
+        .unwrap()
+    };
+
+    let first = analyze("fn a() {} fn b() {} fn c() {}", None);
+
+    let second = analyze("fn a() {} fn c() {}", Some(&first));
+
+    assert_eq!(second.nodes.len(), first.nodes.len());
+    assert_eq!(second.nodes[id(&first, "b")].kind, NodeKind::Removed);
+
+    let third = analyze("fn a() {} fn c() {} fn d() {}", Some(&second));
+
+    assert_eq!(third.nodes.len(), first.nodes.len());
+    assert_eq!(id(&third, "d"), id(&first, "b"));
+    assert_eq!(id(&third, "c"), id(&first, "c"));
+
+    let mut previous = third;
+
+    for source in ["fn a() {} fn c() {}", "fn a() {} fn c() {} fn e() {}"]
+      .into_iter()
+      .cycle()
+      .take(10)
+    {
+      previous = analyze(source, Some(&previous));
+      assert!(previous.nodes.len() <= first.nodes.len() + 1);
+    }
+  }
+}
@@ -0,0 +1,208 @@
+use super::*;
+
+/// The changes that turn one graph into another, keyed by node id, so that a
+/// client holding the earlier graph can be brought up to date without
+/// sending it the whole later one. Ids are positions in a traversal of the
+/// containment tree, so adding or removing an item shifts the ids of the
+/// nodes after it, which then count as changed, unless the later graph
+/// keeps the earlier one's ids with [`Graph::preserve_ids`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPatch {
+  /// The number of nodes in the later graph. Nodes with higher ids were
+  /// removed.
+  pub node_count: usize,
+  /// Nodes that are new, or that differ in any way from the node with the
+  /// same id, in id order.
+  pub changed: Vec<Node>,
+  /// Whether the later graph was expanded, if that changed.
+  pub expanded: Option<bool>,
+  /// The later graph's root, if it moved.
+  pub root: Option<NodeId>,
+  /// The later graph's warnings, if they changed.
+  pub warnings: Option<Vec<Warning>>,
+  /// The later graph's re-exports, if they changed.
+  pub reexports: Option<BTreeMap<String, Vec<String>>>,
+}
+
+impl GraphPatch {
+  /// The changes from `previous` to `next`, or `None` if the patch would
+  /// serialize to no fewer bytes of JSON than `next` itself, in which case
+  /// sending the whole graph is cheaper.
+  pub fn diff(previous: &Graph, next: &Graph) -> Option<Self> {
+    let changed = next
+      .nodes
+      .iter()
+      .filter(|node| previous.nodes.get(node.id) != Some(node))
+      .cloned()
+      .collect::<Vec<Node>>();
+
+    let patch = Self {
+      node_count: next.nodes.len(),
+      changed,
+      expanded: (previous.expanded != next.expanded).then_some(next.expanded),
+      root: (previous.root != next.root).then_some(next.root),
+      warnings: (previous.warnings != next.warnings)
+        .then(|| next.warnings.clone()),
+      reexports: (previous.reexports != next.reexports)
+        .then(|| next.reexports.clone()),
+    };
+
+    (Self::size(&patch) < Self::size(next)).then_some(patch)
+  }
+
+  /// The length of `value` serialized as JSON.
+  fn size(value: &impl Serialize) -> usize {
+    serde_json::to_vec(value).map_or(usize::MAX, |bytes| bytes.len())
+  }
+
+  /// Apply the patch to the graph it was computed from, turning it into the
+  /// graph it was computed to.
+  pub fn apply(self, graph: &mut Graph) {
+    graph.nodes.truncate(self.node_count);
+
+    for node in self.changed {
+      match graph.nodes.get_mut(node.id) {
+        Some(existing) => *existing = node,
+        None => graph.nodes.push(node),
+      }
+    }
+
+    if let Some(expanded) = self.expanded {
+      graph.expanded = expanded;
+    }
+
+    if let Some(root) = self.root {
+      graph.root = root;
+    }
+
+    if let Some(warnings) = self.warnings {
+      graph.warnings = warnings;
+    }
+
+    if let Some(reexports) = self.reexports {
+      graph.reexports = reexports;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn graph(names: &[&str]) -> Graph {
+    Graph {
+      root: 0,
+      nodes: names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| Node {
+          id,
+          name: (*name).into(),
+          children: if id == 0 {
+            (1..names.len()).collect()
+          } else {
+            Vec::new()
+          },
+          ..Node::default()
+        })
+        .collect(),
+      ..Graph::default()
+    }
+  }
+
+  #[track_caller]
+  fn assert_round_trip(previous: &Graph, next: &Graph) {
+    let mut patched = previous.clone();
+
+    GraphPatch::diff(previous, next)
+      .unwrap()
+      .apply(&mut patched);
+
+    assert_eq!(
+      serde_json::to_value(patched).unwrap(),
+      serde_json::to_value(next).unwrap(),
+    );
+  }
+
+  #[test]
+  fn unchanged() {
+    let graph = graph(&["root", "a", "b"]);
+
+    assert_eq!(
+      GraphPatch::diff(&graph, &graph),
+      Some(GraphPatch {
+        node_count: 3,
+        ..GraphPatch::default()
+      }),
+    );
+
+    assert_round_trip(&graph, &graph);
+  }
+
+  #[test]
+  fn changed_and_added_nodes() {
+    let previous = graph(&["root", "a", "b", "c", "d", "e", "f"]);
+
+    let next = graph(&["root", "a", "b", "renamed", "d", "e", "f", "g"]);
+
+    let patch = GraphPatch::diff(&previous, &next).unwrap();
+
+    assert_eq!(
+      patch
+        .changed
+        .iter()
+        .map(|node| node.id)
+        .collect::<Vec<NodeId>>(),
+      [0, 3, 7],
+    );
+
+    assert_round_trip(&previous, &next);
+  }
+
+  #[test]
+  fn removed_nodes() {
+    let previous = graph(&["root", "a", "b", "c", "d", "e", "f"]);
+
+    let next = graph(&["root", "a", "b", "c", "d"]);
+
+    assert_eq!(GraphPatch::diff(&previous, &next).unwrap().node_count, 5);
+
+    assert_round_trip(&previous, &next);
+  }
+
+  #[test]
+  fn root_warnings_and_reexports() {
+    let previous = graph(&["root", "a", "b", "c", "d", "e", "f"]);
+
+    let mut next = previous.clone();
+
+    next.root = 1;
+    next.expanded = true;
+    next.warnings.push(Warning {
+      category: WarningCategory::Parse,
+      message: "expected item".into(),
+      path: "src/lib.rs".into(),
+    });
+    next
+      .reexports
+      .insert("a::B".into(), vec!["a::B".into(), "a::C".into()]);
+
+    let patch = GraphPatch::diff(&previous, &next).unwrap();
+
+    assert_eq!(patch.root, Some(1));
+    assert_eq!(patch.expanded, Some(true));
+    assert!(patch.changed.is_empty());
+
+    assert_round_trip(&previous, &next);
+  }
+
+  #[test]
+  fn larger_than_graph() {
+    let previous = graph(&["root", "a", "b", "c"]);
+
+    let next = graph(&["root", "w", "x", "y", "z"]);
+
+    assert_eq!(GraphPatch::diff(&previous, &next), None);
+  }
+}
@@ -0,0 +1,13 @@
+use super::*;
+
+/// The order in which nodes are numbered, following the containment tree
+/// from the root.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum IdOrder {
+  /// Depth-first pre-order, so that each node's descendants follow it, as in
+  /// reading order.
+  #[default]
+  Dfs,
+  /// Breadth-first, so that nodes are numbered level by level.
+  Bfs,
+}
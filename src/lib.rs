@@ -0,0 +1,193 @@
+//! `eye` analyzes a rust crate or workspace and produces a [`Graph`] of its
+//! packages, modules, and items, along with the dependencies between them.
+//!
+//! ```no_run
+//! let graph = eye::analyze(std::path::Path::new(".")).unwrap();
+//!
+//! for node in &graph.nodes {
+//!   println!("{}: {}", node.id, node.name);
+//! }
+//! ```
+
+use {
+  crate::{
+    analyzer::Analyzer,
+    body_visitor::BodyVisitor,
+    dependency_visitor::DependencyVisitor,
+    external_crate_visitor::ExternalCrateVisitor,
+    macro_rules::MacroRules,
+    pattern_visitor::PatternVisitor,
+    reexports::{Import, Reexports},
+    type_qualifier::TypeQualifier,
+  },
+  anyhow::{anyhow, bail, Error},
+  cargo_metadata::{
+    semver::VersionReq, Dependency, DependencyKind, Metadata, MetadataCommand,
+    Package, Target,
+  },
+  clap::ValueEnum,
+  ignore::WalkBuilder,
+  proc_macro2::{Group, Ident, Span, TokenStream, TokenTree},
+  regex::Regex,
+  serde::{Deserialize, Serialize},
+  std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    env,
+    fmt::{self, Display, Formatter},
+    fs,
+    io::Write,
+    iter,
+    mem::{replace, take},
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    time::{Duration, Instant},
+  },
+  syn::{
+    __private::ToTokens,
+    ext::IdentExt,
+    parse::{ParseStream, Parser},
+    parse_file, parse_str,
+    spanned::Spanned,
+    visit::Visit,
+    visit_mut::VisitMut,
+    Abi, Attribute, Block, BoundLifetimes, ExprUnsafe, Fields, FnArg,
+    GenericArgument, GenericParam, Generics, ImplItem, Item, ItemImpl, ItemMod,
+    ItemTrait, ItemUse, Lit, Macro, Meta, MetaNameValue, NestedMeta, Pat,
+    PatIdent, PathArguments, PathSegment, ReturnType, Signature,
+    TraitBoundModifier, TraitItem, Type, TypeParamBound, TypePath, UseTree,
+    WherePredicate,
+  },
+};
+
+pub use crate::{
+  cfg::Cfg,
+  config::Config,
+  edge::Edge,
+  edge_kind::EdgeKind,
+  edge_scope::EdgeScope,
+  field::Field,
+  function_role::FunctionRole,
+  graph::Graph,
+  graph_patch::GraphPatch,
+  id_order::IdOrder,
+  manifest_dependency::ManifestDependency,
+  method::Method,
+  node::{Node, NodeId},
+  node_kind::NodeKind,
+  predicate::Predicate,
+  profile::{PackageProfile, Profile},
+  record::Record,
+  registry::fetch_crate,
+  sort::Sort,
+  stream_format::StreamFormat,
+  timed_out::TimedOut,
+  visibility::Visibility,
+  warning::Warning,
+  warning_category::WarningCategory,
+};
+
+mod analyzer;
+mod body_visitor;
+mod cargo_expand;
+mod cfg;
+mod config;
+mod dependency_visitor;
+mod edge;
+mod edge_kind;
+mod edge_scope;
+mod external_crate_visitor;
+mod field;
+mod function_role;
+mod git;
+mod graph;
+mod graph_patch;
+mod id_order;
+mod macro_rules;
+mod manifest_dependency;
+mod method;
+mod node;
+mod node_kind;
+mod pattern_visitor;
+mod predicate;
+mod profile;
+mod record;
+mod reexports;
+mod registry;
+mod render;
+mod sort;
+mod stream_format;
+mod timed_out;
+mod type_qualifier;
+mod visibility;
+mod warning;
+mod warning_category;
+
+pub type Result<T = (), E = anyhow::Error> = std::result::Result<T, E>;
+
+/// Analyze the crate or workspace whose `Cargo.toml` lives in `path`.
+pub fn analyze(path: &Path) -> Result<Graph> {
+  analyze_with(path, &Config::default())
+}
+
+/// Like [`analyze`], but with non-default settings.
+pub fn analyze_with(path: &Path, config: &Config) -> Result<Graph> {
+  Analyzer::new(config).analyze(path)
+}
+
+/// Analyze the module tree rooted at the source file `entry`, following its
+/// `mod` declarations, for crates without a usable `Cargo.toml`.
+pub fn analyze_entry(entry: &Path, config: &Config) -> Result<Graph> {
+  Analyzer::new(config).analyze_entry(entry)
+}
+
+/// Analyze a single file's source code, named `name` in the graph, without
+/// following its `mod` declarations.
+pub fn analyze_source(
+  name: &str,
+  source: String,
+  config: &Config,
+) -> Result<Graph> {
+  Analyzer::new(config).analyze_source(name, source)
+}
+
+/// Whether each package that [`analyze_with`] would include from the crate or
+/// workspace in `path` is `#![no_std]`, keyed by name, found from the crate's
+/// metadata and the inner attributes of its crate root files, without
+/// analyzing anything else.
+pub fn no_std(path: &Path, config: &Config) -> Result<BTreeMap<String, bool>> {
+  Analyzer::new(config).no_std(path)
+}
+
+/// Like [`analyze_with`], but write the graph to `writer` as a stream of
+/// [`Record`]s in `format`, instead of returning it, so that no serialized
+/// copy of the whole graph is ever held in memory, and each node is freed as
+/// soon as it's written. Records are written in the order documented on
+/// [`Record`], and `writer` is flushed once the last is written.
+///
+/// Memory use is not bounded: analysis builds the whole graph before the
+/// first record is written, since references between items can only be
+/// resolved once every file has been parsed, so peak memory is that of
+/// [`analyze_with`]. Streaming only saves holding the serialized output.
+pub fn analyze_to_writer<W: Write>(
+  path: &Path,
+  config: &Config,
+  mut writer: W,
+  format: StreamFormat,
+) -> Result {
+  for record in Record::stream(analyze_with(path, config)?) {
+    format.write(&mut writer, &record)?;
+  }
+
+  writer.flush()?;
+
+  Ok(())
+}
+
+/// Like [`analyze_with`], but also report how long each phase took.
+pub fn profile(path: &Path, config: &Config) -> Result<(Graph, Profile)> {
+  let mut analyzer = Analyzer::new(config);
+  let graph = analyzer.analyze(path)?;
+  Ok((graph, analyzer.profile().clone()))
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+/// The rules of a `macro_rules!` definition, which can expand invocations
+/// whose rules use only simple fragments, without repetitions.
+#[derive(Debug)]
+pub(crate) struct MacroRules {
+  rules: Vec<(TokenStream, TokenStream)>,
+}
+
+impl MacroRules {
+  /// Split a definition's body into `(matcher) => { transcriber }` rules.
+  pub(crate) fn parse(body: TokenStream) -> Self {
+    let mut rules = Vec::new();
+
+    let mut tokens = body.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+      let TokenTree::Group(matcher) = token else {
+        continue;
+      };
+
+      let arrow = (tokens.next(), tokens.next());
+
+      let (Some(TokenTree::Punct(eq)), Some(TokenTree::Punct(gt))) = arrow
+      else {
+        break;
+      };
+
+      if eq.as_char() != '=' || gt.as_char() != '>' {
+        break;
+      }
+
+      let Some(TokenTree::Group(transcriber)) = tokens.next() else {
+        break;
+      };
+
+      rules.push((matcher.stream(), transcriber.stream()));
+    }
+
+    Self { rules }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.rules.len()
+  }
+
+  /// Expand an invocation with the first rule that matches it.
+  pub(crate) fn expand(&self, input: &TokenStream) -> Option<TokenStream> {
+    self.rules.iter().find_map(|(matcher, transcriber)| {
+      let mut bindings = HashMap::new();
+
+      (|stream: ParseStream| Self::matches(matcher, stream, &mut bindings))
+        .parse2(input.clone())
+        .ok()?;
+
+      Self::transcribe(transcriber, &bindings)
+    })
+  }
+
+  /// Match `input` against `matcher`, binding each `$name:fragment`.
+  fn matches(
+    matcher: &TokenStream,
+    input: ParseStream,
+    bindings: &mut HashMap<String, TokenStream>,
+  ) -> syn::Result<()> {
+    let mut tokens = matcher.clone().into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+      match token {
+        TokenTree::Punct(dollar) if dollar.as_char() == '$' => {
+          let Some(TokenTree::Ident(name)) = tokens.next() else {
+            return Err(input.error("unsupported repetition"));
+          };
+
+          let (Some(TokenTree::Punct(_)), Some(TokenTree::Ident(fragment))) =
+            (tokens.next(), tokens.next())
+          else {
+            return Err(input.error("malformed fragment"));
+          };
+
+          let tokens = match fragment.to_string().as_str() {
+            "block" => input.parse::<Block>()?.to_token_stream(),
+            "expr" => input.parse::<syn::Expr>()?.to_token_stream(),
+            "ident" => input.call(Ident::parse_any)?.to_token_stream(),
+            "item" => input.parse::<Item>()?.to_token_stream(),
+            "lifetime" => input.parse::<syn::Lifetime>()?.to_token_stream(),
+            "literal" => input.parse::<syn::Lit>()?.to_token_stream(),
+            "path" => input.parse::<syn::Path>()?.to_token_stream(),
+            "tt" => input.parse::<TokenTree>()?.to_token_stream(),
+            "ty" => input.parse::<syn::Type>()?.to_token_stream(),
+            "vis" => input.parse::<syn::Visibility>()?.to_token_stream(),
+            _ => return Err(input.error("unsupported fragment")),
+          };
+
+          bindings.insert(name.to_string(), tokens);
+        }
+        TokenTree::Group(expected) => {
+          let TokenTree::Group(actual) = input.parse::<TokenTree>()? else {
+            return Err(input.error("expected group"));
+          };
+
+          if actual.delimiter() != expected.delimiter() {
+            return Err(input.error("mismatched delimiter"));
+          }
+
+          let expected = expected.stream();
+
+          (|stream: ParseStream| Self::matches(&expected, stream, bindings))
+            .parse2(actual.stream())?;
+        }
+        expected => {
+          let actual = input.parse::<TokenTree>()?;
+
+          if actual.to_string() != expected.to_string() {
+            return Err(input.error("unexpected token"));
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Substitute bound fragments into `transcriber`.
+  fn transcribe(
+    transcriber: &TokenStream,
+    bindings: &HashMap<String, TokenStream>,
+  ) -> Option<TokenStream> {
+    let mut output = TokenStream::new();
+
+    let mut tokens = transcriber.clone().into_iter();
+
+    while let Some(token) = tokens.next() {
+      match token {
+        TokenTree::Punct(dollar) if dollar.as_char() == '$' => {
+          let TokenTree::Ident(name) = tokens.next()? else {
+            return None;
+          };
+
+          if name == "crate" {
+            output.extend([TokenTree::Ident(name)]);
+          } else {
+            output.extend(bindings.get(&name.to_string())?.clone());
+          }
+        }
+        TokenTree::Group(group) => {
+          let mut expanded = Group::new(
+            group.delimiter(),
+            Self::transcribe(&group.stream(), bindings)?,
+          );
+
+          expanded.set_span(group.span());
+
+          output.extend([TokenTree::Group(expanded)]);
+        }
+        token => output.extend([token]),
+      }
+    }
+
+    Some(output)
+  }
+}
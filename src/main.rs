@@ -1,495 +1,295 @@
 use {
-  axum::{extract::State, routing::get, Json, Router},
-  cargo_metadata::{MetadataCommand, Package},
-  clap::Parser,
-  serde::Serialize,
+  crate::{
+    color::Color, format::Format, presets::Presets, subcommand::Subcommand,
+  },
+  anyhow::{anyhow, bail, Context},
+  axum::{
+    body::{self, Body},
+    extract::{Path, Query, State},
+    http::{
+      header::{ACCEPT, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+      HeaderMap, HeaderName, StatusCode,
+    },
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+  },
+  clap::{builder::PossibleValuesParser, ArgGroup, Parser, ValueEnum},
+  eye::{
+    Cfg, Config, EdgeKind, Graph, IdOrder, ManifestDependency, Method, Node,
+    NodeId, NodeKind, Sort, TimedOut, Visibility, Warning, WarningCategory,
+  },
+  hyper_util::{
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+    server::conn::auto,
+    service::TowerToHyperService,
+  },
+  regex::Regex,
+  serde::{Deserialize, Serialize},
+  serde_json::{json, ser::PrettyFormatter, Value},
   std::{
-    collections::HashSet,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    env,
+    ffi::OsString,
+    fmt::Display,
     fs,
-    mem::take,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, IsTerminal, Write},
     net::SocketAddr,
-    path::{Path, PathBuf},
-    process,
-    sync::Arc,
+    os::unix::fs::FileTypeExt,
+    path::PathBuf,
+    process, slice,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
   },
-  syn::{
-    __private::ToTokens, parse_file, visit::Visit, Fields, FnArg, Item,
-    ItemStruct, ReturnType,
+  tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, UnixListener},
+    signal,
+    sync::{mpsc, Semaphore},
+    task, time,
   },
-  tokio::net::TcpListener,
+  tokio_stream::wrappers::ReceiverStream,
   tower_http::cors::CorsLayer,
   tracing::{error, info},
   tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt},
-  walkdir::WalkDir,
 };
 
-#[derive(Default, Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct Graph {
-  root: NodeId,
-  nodes: Vec<Node>,
-}
-
-type NodeId = usize;
-
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct Node {
-  id: NodeId,
-  name: String,
-  kind: NodeKind,
-  children: Vec<NodeId>,
-  documentation: String,
-  source_code: String,
-}
-
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-#[serde(tag = "type", content = "content")]
-enum NodeKind {
-  Workspace {
-    path: PathBuf,
-  },
-  Package {
-    path: PathBuf,
-  },
-  Module {
-    path: PathBuf,
-  },
-  Struct {
-    fields: Vec<Field>,
-  },
-  Enum {
-    variants: Vec<String>,
-  },
-  Function {
-    arguments: Vec<Field>,
-    return_type: Option<String>,
-  },
-  Const {
-    ty: String,
-    value: String,
-  },
-  Macro {
-    macro_rules: bool,
-  },
-  Static {
-    ty: String,
-    mutability: bool,
-  },
-  Trait {
-    is_auto: bool,
-    is_unsafe: bool,
-  },
-  TraitAlias {
-    generics: String,
-  },
-  Type {
-    generics: String,
-  },
-  Unknown,
-}
-
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct Field {
-  name: String,
-  #[serde(rename = "typeName")]
-  type_name: String,
-}
-
-struct Analyzer {
-  graph: Graph,
+#[derive(Debug, Parser)]
+#[clap(group(ArgGroup::new("source")))]
+struct Options {
+  #[clap(long, short, group = "source")]
+  crate_path: Option<PathBuf>,
+  /// Download and analyze a crate from crates.io
+  #[clap(long = "crate", value_name = "NAME[@VERSION]", group = "source")]
+  krate: Option<String>,
+  /// Analyze a single source file, or standard input if `-`, without
+  /// following its `mod` declarations
+  #[clap(long, value_name = "PATH", group = "source")]
+  file: Option<PathBuf>,
+  #[clap(skip)]
+  stdin: Option<String>,
+  /// Analyze the module tree rooted at this file, following its `mod`
+  /// declarations, without a `Cargo.toml`
+  #[clap(long, value_name = "PATH", group = "source")]
+  entry: Option<PathBuf>,
+  /// Report file paths as absolute, rather than relative to the crate
+  #[clap(long)]
+  absolute_paths: bool,
+  /// Merge metadata from this JSON file, mapping path globs like
+  /// `app::db::*` to objects, into the annotations of matching items
+  #[clap(long, value_name = "PATH")]
+  annotations: Option<PathBuf>,
+  /// Only analyze the binary target with this name, following its module
+  /// tree
+  #[clap(long, value_name = "NAME", conflicts_with = "src_dir")]
+  bin: Option<String>,
+  /// Analyze as if this cfg option were set, leaving out items whose `cfg`
+  /// attributes are false, like `--cfg unix` or `--cfg feature=x`
+  #[clap(long, value_name = "NAME[=VALUE]")]
+  cfg: Vec<Cfg>,
+  /// Color human-readable output
+  #[clap(long, value_enum, value_name = "WHEN", default_value_t = Color::Auto)]
+  color: Color,
+  /// Show re-exported items under their shortest public path
+  #[clap(long)]
+  collapse_reexports: bool,
+  /// Analyze each target as expanded by `cargo expand`, which must be
+  /// installed, so that items generated by macros appear
+  #[clap(long, conflicts_with_all = ["file", "entry", "src_dir"])]
+  expand: bool,
+  /// Expand invocations of the crate's own `macro_rules!` macros
+  #[clap(long)]
+  expand_macros: bool,
+  /// Count the commits that touched each function and type using `git log -L`
+  #[clap(long)]
+  git_churn: bool,
+  /// Traversal of the containment tree in which node ids are assigned
+  #[clap(long, value_enum, default_value_t = IdOrder::Dfs)]
+  id_order: IdOrder,
+  /// List every module directly under its package, named by its full path
+  #[clap(long)]
+  flatten: bool,
+  /// Skip source files larger than this, such as generated code, leaving an
+  /// empty module for each and a warning
+  #[clap(long, value_name = "BYTES")]
+  max_file_size: Option<u64>,
+  /// Read the crate's metadata from this file, saved from `cargo metadata
+  /// --no-deps --format-version 1`, rather than running cargo
+  #[clap(long, value_name = "PATH")]
+  metadata: Option<PathBuf>,
+  /// Only keep items whose name matches this regular expression, along with
+  /// their ancestors
+  #[clap(long, value_name = "REGEX")]
+  name_filter: Option<Regex>,
+  /// Skip tracing dependencies between items, keeping only the containment
+  /// tree and edges found without tracing
+  #[clap(long)]
+  no_deps_edges: bool,
+  /// Analyze source files excluded by `.gitignore` rules
+  #[clap(long)]
+  no_ignore: bool,
+  /// Drop nodes of this kind from the graph
+  #[clap(
+    long = "exclude-kind",
+    value_name = "KIND",
+    value_parser = PossibleValuesParser::new(NodeKind::NAMES)
+  )]
+  exclude_kinds: Vec<String>,
+  /// Only keep items changed since `--since`, along with their ancestors
+  #[clap(long, requires = "since")]
+  only_changed: bool,
+  /// Only analyze this workspace member
+  #[clap(long = "package", value_name = "NAME")]
+  packages: Vec<String>,
+  /// Name in-crate types in field, argument, and return types by their
+  /// canonical paths, like `crate::server::Config`
+  #[clap(long)]
+  qualify_types: bool,
+  /// Only keep items reachable from outside the crate, by a public path or
+  /// through the signature of another reachable item, along with their
+  /// ancestors
+  #[clap(long)]
+  reachable_only: bool,
+  /// Replace matches of this regular expression in source code and
+  /// documentation with `[REDACTED]`, on a best-effort basis
+  #[clap(long, value_name = "REGEX")]
+  redact: Vec<Regex>,
+  /// Only link items to those with a matching name that are visible from the
+  /// referencing module
+  #[clap(long)]
+  respect_visibility: bool,
+  /// Start from the options of this profile in `.eye.toml`, which options
+  /// given on the command line override
+  #[clap(long, value_name = "NAME")]
+  profile: Option<String>,
+  /// Only analyze each target's crate root file
+  #[clap(long)]
+  root_module_only: bool,
+  /// Mark items whose lines differ from this git revision as changed
+  #[clap(long, value_name = "REF")]
+  since: Option<String>,
+  #[clap(long, value_enum, default_value_t = Sort::Source)]
+  sort: Sort,
+  /// Analyze every source file in this directory, relative to each package's
+  /// manifest, instead of following `mod` declarations from each target
+  #[clap(long, value_name = "DIR")]
+  src_dir: Option<PathBuf>,
+  /// Fail, listing every warning, if any file can't be fully analyzed
+  #[clap(long)]
+  strict: bool,
+  /// Abort analysis that takes longer than this many seconds
+  #[clap(long, value_name = "SECONDS")]
+  timeout: Option<u64>,
+  /// Write analysis warnings to this file, one JSON object per line
+  #[clap(long, value_name = "PATH")]
+  warnings_file: Option<PathBuf>,
 }
 
-impl Analyzer {
-  fn new() -> Self {
-    Self {
-      graph: Graph {
-        root: 0,
-        nodes: Vec::new(),
-      },
+impl Options {
+  /// Fetch the crate named by `--crate`, if any, so that `crate_path` is set,
+  /// and read standard input if it's the `--file`, since it can only be read
+  /// once.
+  fn resolve(&mut self) -> Result {
+    if let Some(spec) = &self.krate {
+      info!("Fetching crate: {spec}");
+      self.crate_path = Some(eye::fetch_crate(spec)?);
     }
-  }
-
-  fn analyze(&mut self, crate_path: &Path) -> Result<Graph> {
-    let metadata = MetadataCommand::new()
-      .manifest_path(crate_path.join("Cargo.toml"))
-      .no_deps()
-      .exec()?;
-
-    let workspace_members = metadata
-      .workspace_members
-      .into_iter()
-      .collect::<HashSet<_>>();
 
-    let is_proper_workspace = workspace_members.len() > 1;
-
-    if is_proper_workspace {
-      self.graph.nodes.push(Node {
-        id: 0,
-        name: crate_path
-          .file_name()
-          .unwrap()
-          .to_string_lossy()
-          .into_owned(),
-        kind: NodeKind::Workspace {
-          path: crate_path.to_path_buf(),
-        },
-        children: Vec::new(),
-        documentation: String::new(),
-        source_code: String::new(),
-      });
-    }
-
-    for package in metadata.packages {
-      if workspace_members.contains(&package.id) {
-        self.handle_package(&package, 0, is_proper_workspace)?;
-      }
-    }
-
-    Ok(take(&mut self.graph))
-  }
-
-  fn handle_package(
-    &mut self,
-    package: &Package,
-    parent_id: NodeId,
-    is_workspace: bool,
-  ) -> Result {
-    let package_id = self.graph.nodes.len();
-
-    let package_node = Node {
-      id: package_id,
-      name: package.name.clone(),
-      kind: NodeKind::Package {
-        path: package.manifest_path.parent().unwrap().to_path_buf().into(),
-      },
-      children: Vec::new(),
-      documentation: package.description.clone().unwrap_or_default(),
-      source_code: String::new(),
-    };
-
-    self.graph.nodes.push(package_node);
-
-    if is_workspace {
-      self.graph.nodes[parent_id].children.push(package_id);
-    }
-
-    let src_path = package.manifest_path.parent().unwrap().join("src");
-
-    let entries = WalkDir::new(&src_path)
-      .into_iter()
-      .filter_map(Result::ok)
-      .filter(|entry| {
-        entry.file_type().is_file()
-          && entry.path().extension().map_or(false, |ext| ext == "rs")
-      });
-
-    for entry in entries {
-      let file_path = entry.path();
-
-      let file_content = fs::read_to_string(file_path)?;
-
-      let syntax = parse_file(&file_content)?;
-
-      let module_name = file_path
-        .strip_prefix(&src_path)?
-        .to_string_lossy()
-        .into_owned();
-
-      let module_id = self.graph.nodes.len();
-
-      let module_node = Node {
-        id: module_id,
-        name: module_name,
-        kind: NodeKind::Module {
-          path: file_path.to_path_buf(),
-        },
-        children: Vec::new(),
-        documentation: String::new(),
-        source_code: file_content,
-      };
-
-      self.graph.nodes.push(module_node);
-      self.graph.nodes[parent_id].children.push(module_id);
-
-      self.handle_syntactic_items(&syntax.items, file_path, module_id)?;
+    if self.file.as_deref() == Some(std::path::Path::new("-")) {
+      self.stdin = Some(
+        io::read_to_string(io::stdin())
+          .context("failed to read standard input")?,
+      );
     }
 
     Ok(())
   }
 
-  fn handle_syntactic_items(
-    &mut self,
-    items: &[Item],
-    file_path: &Path,
-    parent_id: NodeId,
-  ) -> Result {
-    for item in items {
-      let source_code = item.to_token_stream().to_string();
-
-      // tracing::info!("Processing item: {}", source_code);
-
-      let node_id = self.graph.nodes.len();
-
-      let mut node = Node {
-        id: node_id,
-        name: String::new(),
-        kind: NodeKind::Unknown,
-        children: Vec::new(),
-        documentation: String::new(),
-        source_code,
-      };
-
-      match item {
-        Item::Const(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Const {
-            ty: i.ty.to_token_stream().to_string(),
-            value: i.expr.to_token_stream().to_string(),
-          };
-        }
-        Item::Enum(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Enum {
-            variants: i.variants.iter().map(|v| v.ident.to_string()).collect(),
-          };
-        }
-        Item::Fn(i) => {
-          node.name = i.sig.ident.to_string();
-          node.kind = NodeKind::Function {
-            arguments: i
-              .sig
-              .inputs
-              .iter()
-              .filter_map(|arg| {
-                if let FnArg::Typed(pat_type) = arg {
-                  Some(Field {
-                    name: pat_type.pat.to_token_stream().to_string(),
-                    type_name: pat_type.ty.to_token_stream().to_string(),
-                  })
-                } else {
-                  None
-                }
-              })
-              .collect(),
-            return_type: match &i.sig.output {
-              ReturnType::Default => None,
-              ReturnType::Type(_, ty) => Some(ty.to_token_stream().to_string()),
-            },
-          };
-        }
-        Item::Macro(i) => {
-          node.name = i
-            .ident
-            .as_ref()
-            .map_or("macro".to_string(), |ident| ident.to_string());
-          node.kind = NodeKind::Macro {
-            macro_rules: i.mac.path.is_ident("macro_rules"),
-          };
-        }
-        Item::Macro2(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Macro { macro_rules: false };
-        }
-        Item::Mod(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Module {
-            path: file_path.to_path_buf(),
-          };
-          if let Some((_, items)) = &i.content {
-            self.handle_syntactic_items(items, file_path, node_id)?;
-          }
-        }
-        Item::Static(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Static {
-            ty: i.ty.to_token_stream().to_string(),
-            mutability: i.mutability.is_some(),
-          };
-        }
-        Item::Struct(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Struct {
-            fields: Self::handle_struct_fields(i),
-          };
-        }
-        Item::Trait(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Trait {
-            is_auto: i.auto_token.is_some(),
-            is_unsafe: i.unsafety.is_some(),
-          };
-        }
-        Item::TraitAlias(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::TraitAlias {
-            generics: i.generics.to_token_stream().to_string(),
-          };
-        }
-        Item::Type(i) => {
-          node.name = i.ident.to_string();
-          node.kind = NodeKind::Type {
-            generics: i.generics.to_token_stream().to_string(),
-          };
-        }
-        _ => continue,
+  fn crate_path(&self) -> Result<&std::path::Path> {
+    match &self.crate_path {
+      Some(crate_path) => Ok(crate_path),
+      None if self.file.is_some() || self.entry.is_some() => {
+        bail!("this subcommand can't analyze a `--file` or `--entry`")
       }
-
-      self.graph.nodes.push(node);
-      self.graph.nodes[parent_id].children.push(node_id);
-
-      self.trace_dependencies(item, node_id, parent_id);
-    }
-
-    Ok(())
-  }
-
-  fn trace_dependencies(
-    &mut self,
-    item: &Item,
-    current_id: NodeId,
-    current_module_id: NodeId,
-  ) {
-    let mut visitor =
-      DependencyVisitor::new(&mut self.graph, current_id, current_module_id);
-
-    match item {
-      Item::Const(i) => visitor.visit_item_const(i),
-      Item::Enum(i) => visitor.visit_item_enum(i),
-      Item::ExternCrate(i) => visitor.visit_item_extern_crate(i),
-      Item::Fn(i) => visitor.visit_item_fn(i),
-      Item::ForeignMod(i) => visitor.visit_item_foreign_mod(i),
-      Item::Impl(i) => visitor.visit_item_impl(i),
-      Item::Mod(i) => visitor.visit_item_mod(i),
-      Item::Static(i) => visitor.visit_item_static(i),
-      Item::Struct(i) => visitor.visit_item_struct(i),
-      Item::Trait(i) => visitor.visit_item_trait(i),
-      Item::TraitAlias(i) => visitor.visit_item_trait_alias(i),
-      Item::Type(i) => visitor.visit_item_type(i),
-      Item::Union(i) => visitor.visit_item_union(i),
-      Item::Use(i) => visitor.visit_item_use(i),
-      _ => {}
-    }
-  }
-
-  fn handle_struct_fields(item_struct: &ItemStruct) -> Vec<Field> {
-    match item_struct {
-      ItemStruct {
-        fields: Fields::Named(named_fields),
-        ..
-      } => named_fields
-        .named
-        .iter()
-        .map(|field| Field {
-          name: field.ident.as_ref().unwrap().to_string(),
-          type_name: field.ty.to_token_stream().to_string(),
-        })
-        .collect(),
-      _ => Vec::new(),
-    }
-  }
-}
-
-struct DependencyVisitor<'a> {
-  graph: &'a mut Graph,
-  current_id: NodeId,
-  current_module_id: NodeId,
-}
-
-impl<'a> DependencyVisitor<'a> {
-  fn new(
-    graph: &'a mut Graph,
-    current_id: NodeId,
-    current_module_id: NodeId,
-  ) -> Self {
-    Self {
-      graph,
-      current_id,
-      current_module_id,
+      None => bail!(
+        "one of `--crate-path`, `--crate`, `--file`, or `--entry` is required"
+      ),
     }
   }
 
-  fn find_node_by_name(&self, name: &str) -> Option<NodeId> {
-    self.graph.nodes.iter().position(|node| node.name == name)
-  }
-
-  fn find_node_in_module(
-    &self,
-    module_id: NodeId,
-    name: &str,
-  ) -> Option<NodeId> {
-    self.graph.nodes[module_id]
-      .children
-      .iter()
-      .find(|&&child_id| self.graph.nodes[child_id].name == name)
-      .cloned()
-  }
-
-  fn add_dependency(&mut self, target_id: NodeId) {
-    if !self.graph.nodes[self.current_id]
-      .children
-      .contains(&target_id)
-    {
-      self.graph.nodes[self.current_id].children.push(target_id);
-    }
-  }
-}
+  /// Analyze the crate or file, writing any warnings to `--warnings-file`.
+  fn analyze(&self, config: &Config) -> Result<Graph> {
+    let graph = match (&self.file, &self.entry, &self.stdin) {
+      (_, _, Some(source)) => {
+        eye::analyze_source("<stdin>", source.clone(), config)?
+      }
+      (Some(path), _, None) => eye::analyze_source(
+        &path.display().to_string(),
+        fs::read_to_string(path)
+          .with_context(|| format!("failed to read `{}`", path.display()))?,
+        config,
+      )?,
+      (None, Some(entry), None) => eye::analyze_entry(entry, config)?,
+      (None, None, None) => eye::analyze_with(self.crate_path()?, config)?,
+    };
 
-impl<'ast> Visit<'ast> for DependencyVisitor<'_> {
-  fn visit_path(&mut self, path: &'ast syn::Path) {
-    if let Some(ident) = path.get_ident() {
-      let name = ident.to_string();
+    if let Some(path) = &self.warnings_file {
+      let mut lines = String::new();
 
-      if let Some(target_id) =
-        self.find_node_in_module(self.current_module_id, &name)
-      {
-        self.add_dependency(target_id);
-      } else {
-        if let Some(target_id) = self.find_node_by_name(&name) {
-          self.add_dependency(target_id);
-        }
+      for warning in &graph.warnings {
+        lines.push_str(&serde_json::to_string(warning)?);
+        lines.push('\n');
       }
-    } else {
-      let mut current_module_id = self.current_module_id;
 
-      for segment in path.segments.iter() {
-        let name = segment.ident.to_string();
-        if let Some(target_id) =
-          self.find_node_in_module(current_module_id, &name)
-        {
-          self.add_dependency(target_id);
-          current_module_id = target_id;
-        } else {
-          break;
-        }
-      }
+      fs::write(path, lines).with_context(|| {
+        format!("failed to write warnings to `{}`", path.display())
+      })?;
     }
 
-    syn::visit::visit_path(self, path);
-  }
-
-  fn visit_item(&mut self, i: &'ast syn::Item) {
-    syn::visit::visit_item(self, i);
+    Ok(graph)
   }
 
-  fn visit_type(&mut self, ty: &'ast syn::Type) {
-    syn::visit::visit_type(self, ty);
-  }
-
-  fn visit_expr(&mut self, expr: &'ast syn::Expr) {
-    syn::visit::visit_expr(self, expr);
+  fn config(&self) -> Config {
+    Config {
+      absolute_paths: self.absolute_paths,
+      annotations: self.annotations.clone(),
+      bin: self.bin.clone(),
+      cfg: self.cfg.clone(),
+      collapse_reexports: self.collapse_reexports,
+      exclude_kinds: self.exclude_kinds.clone(),
+      expand: self.expand,
+      expand_macros: self.expand_macros,
+      flatten: self.flatten,
+      git_churn: self.git_churn,
+      id_order: self.id_order,
+      max_file_size: self.max_file_size,
+      metadata: self.metadata.clone(),
+      name_filter: self.name_filter.clone(),
+      no_deps_edges: self.no_deps_edges,
+      no_ignore: self.no_ignore,
+      only_changed: self.only_changed,
+      packages: self.packages.clone(),
+      qualify_types: self.qualify_types,
+      reachable_only: self.reachable_only,
+      redact: self.redact.clone(),
+      respect_visibility: self.respect_visibility,
+      root_module_only: self.root_module_only,
+      since: self.since.clone(),
+      sort: self.sort,
+      src_dir: self.src_dir.clone(),
+      strict: self.strict,
+      timeout: self.timeout.map(Duration::from_secs),
+      visibility: None,
+    }
   }
 }
 
 #[derive(Debug, Parser)]
-struct Options {
-  #[clap(long, short)]
-  crate_path: PathBuf,
-}
-
-#[derive(Debug, Parser)]
+#[clap(args_override_self = true)]
 struct Arguments {
   #[clap(flatten)]
   options: Options,
@@ -498,80 +298,59 @@ struct Arguments {
 }
 
 impl Arguments {
-  async fn run(self) -> Result {
-    self.subcommand.run(self.options).await
-  }
-}
-
-#[derive(Debug, Parser)]
-enum Subcommand {
-  Serve(Server),
-}
-
-impl Subcommand {
-  async fn run(self, options: Options) -> Result {
-    match self {
-      Subcommand::Serve(server) => server.run(options).await,
-    }
-  }
-}
-
-#[derive(Debug, Parser)]
-struct Server {
-  #[clap(short, long, default_value = "8000")]
-  port: u16,
-}
-
-impl Server {
-  async fn run(self, options: Options) -> Result {
-    let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
-
-    info!("Listening on port: {}", addr.port());
-
-    let state = Arc::new(options);
-
-    let router = Router::new()
-      .route("/api/graph", get(Self::graph))
-      .with_state(state)
-      .layer(CorsLayer::permissive());
+  /// Parse the command line, with the options of the `--profile`, if any,
+  /// inserted before it, so that options given on the command line, which
+  /// come later, take precedence.
+  fn load() -> Result<Self> {
+    let arguments = Self::parse();
+
+    let Some(profile) = &arguments.options.profile else {
+      return Ok(arguments);
+    };
 
-    let listener = TcpListener::bind(addr).await?;
+    let mut command_line = env::args_os().collect::<Vec<OsString>>();
 
-    axum::serve(listener, router.into_make_service()).await?;
+    command_line.splice(
+      1..1,
+      Presets::load()?
+        .arguments(profile)?
+        .into_iter()
+        .map(OsString::from),
+    );
 
-    Ok(())
+    Ok(Self::parse_from(command_line))
   }
 
-  async fn graph(State(options): State<Arc<Options>>) -> Json<Graph> {
-    let mut analyzer = Analyzer::new();
+  async fn run(mut self) -> Result {
+    self.options.color = self.options.color.resolve();
 
-    match analyzer.analyze(&options.crate_path) {
-      Ok(graph) => Json(graph),
-      Err(e) => {
-        error!("Error analyzing crate: {:?}", e);
+    tracing_subscriber::registry()
+      .with(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+          .unwrap_or_else(|_| "info".into()),
+      )
+      .with(
+        tracing_subscriber::fmt::layer()
+          .with_ansi(self.options.color == Color::Always),
+      )
+      .init();
 
-        Json(Graph {
-          root: 0,
-          nodes: vec![],
-        })
-      }
-    }
+    self.options.resolve()?;
+
+    self.subcommand.run(self.options).await
   }
 }
 
+mod color;
+mod format;
+mod presets;
+mod subcommand;
+
 type Result<T = (), E = anyhow::Error> = std::result::Result<T, E>;
 
 #[tokio::main]
 async fn main() {
-  tracing_subscriber::registry()
-    .with(
-      tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "info".into()),
-    )
-    .with(tracing_subscriber::fmt::layer())
-    .init();
-
-  if let Err(error) = Arguments::parse().run().await {
+  if let Err(error) = async { Arguments::load()?.run().await }.await {
     eprintln!("{error}");
     process::exit(1);
   }
@@ -0,0 +1,42 @@
+use super::*;
+
+/// A dependency as declared in a package's `Cargo.toml`, whether or not it
+/// was resolved.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDependency {
+  /// The name of the depended-on package.
+  pub name: String,
+  /// The name the package refers to the dependency by, if it's renamed.
+  pub rename: Option<String>,
+  /// The version requirement, such as `^1.0.86`.
+  pub req: String,
+  /// `normal`, `dev`, or `build`.
+  pub kind: String,
+  pub optional: bool,
+  pub uses_default_features: bool,
+  /// Features of the dependency that the package enables.
+  pub features: Vec<String>,
+  /// The platform the dependency is limited to, such as `cfg(unix)`.
+  pub target: Option<String>,
+}
+
+impl From<&Dependency> for ManifestDependency {
+  fn from(dependency: &Dependency) -> Self {
+    Self {
+      name: dependency.name.clone(),
+      rename: dependency.rename.clone(),
+      req: dependency.req.to_string(),
+      kind: match dependency.kind {
+        DependencyKind::Development => "dev",
+        DependencyKind::Build => "build",
+        _ => "normal",
+      }
+      .into(),
+      optional: dependency.optional,
+      uses_default_features: dependency.uses_default_features,
+      features: dependency.features.clone(),
+      target: dependency.target.as_ref().map(ToString::to_string),
+    }
+  }
+}
@@ -0,0 +1,16 @@
+use super::*;
+
+/// A method callable on a type, from one of the crate's `impl` blocks or
+/// inherited from a default in a trait the crate defines.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Method {
+  pub name: String,
+  /// The trait the method belongs to, or `None` for an inherent method.
+  pub trait_name: Option<String>,
+  /// The in-crate trait node, if the trait is one.
+  pub trait_id: Option<NodeId>,
+  /// Whether the method is a trait's default that the implementation doesn't
+  /// override, like rustdoc's provided methods.
+  pub inherited: bool,
+}
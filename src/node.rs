@@ -0,0 +1,163 @@
+use super::*;
+
+/// An index into [`Graph::nodes`].
+pub type NodeId = usize;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+  pub id: NodeId,
+  pub name: String,
+  pub kind: NodeKind,
+  pub visibility: Visibility,
+  /// Number of containment edges between the root and the node.
+  pub depth: usize,
+  /// The node's position among its parent's children.
+  pub sibling_index: usize,
+  /// The item's position among the items declared in its file, in source
+  /// order, regardless of how children are sorted. Zero for nodes that
+  /// aren't items, such as file modules.
+  pub declaration_order: usize,
+  /// The line in its file where the node starts.
+  pub line: Option<usize>,
+  /// Whether code outside the crate can reach the item, either by a public
+  /// path or because another reachable item names it in its signature, such
+  /// as a private type returned by a public function.
+  pub publicly_reachable: bool,
+  /// Whether the node overlaps lines changed since [`Config::since`].
+  pub changed: bool,
+  /// Number of commits that touched a function or type definition, if
+  /// [`Config::git_churn`] is set.
+  pub churn: Option<u32>,
+  /// Contained items.
+  pub children: Vec<NodeId>,
+  /// Items this node depends on.
+  pub edges: Vec<Edge>,
+  /// The item's trait bounds, including those on traits outside the crate,
+  /// rendered with their generic arguments and any `for<'a>` binder, like
+  /// `Iterator<Item = u8>` or `for<'a> Fn(&'a str) -> T`.
+  pub bounds: Vec<String>,
+  /// The predicates of a generic item's `where` clause, in order.
+  pub where_clause: Vec<Predicate>,
+  /// Alternate names from `#[doc(alias)]` attributes, for search.
+  pub aliases: Vec<String>,
+  /// Whether the item is marked `#[doc(hidden)]`, so that it's left out of
+  /// public API listings unless doc-hidden items are included. Items inside
+  /// a hidden module aren't marked themselves.
+  pub doc_hidden: bool,
+  /// Lifetime parameters of a generic function, type, or trait, such as
+  /// `'a`, which are also part of any rendered generics.
+  pub lifetimes: Vec<String>,
+  /// The canonical path of the item's definition, if it has been moved to
+  /// its public path by [`Config::collapse_reexports`], hiding where it's
+  /// defined.
+  pub definition_path: Option<String>,
+  /// An earlier item with the same name in the same module and namespace,
+  /// making references to the name ambiguous.
+  pub duplicate_of: Option<NodeId>,
+  /// Paths of attributes that are likely proc macros, such as `tokio::main`,
+  /// meaning `source_code` is the item as written rather than as expanded.
+  pub transformed_by: Vec<String>,
+  /// Number of fields of a struct or union, variants of an enum, or items of
+  /// a trait or impl block.
+  pub member_count: Option<usize>,
+  /// Arbitrary metadata merged in from [`Config::annotations`], such as a
+  /// team or layer.
+  pub annotations: BTreeMap<String, serde_json::Value>,
+  pub documentation: String,
+  /// Fenced Rust code blocks from the documentation, such as usage examples.
+  pub doc_examples: Vec<String>,
+  pub source_code: String,
+}
+
+impl Node {
+  /// The item's declaration, normalized by parsing and printing its tokens,
+  /// without documentation or bodies, such as a function's signature or a
+  /// trait without its default methods' bodies. `None` if the node's source
+  /// isn't a single item, such as for a file module.
+  pub fn signature(&self) -> Option<String> {
+    fn strip_docs(attrs: &mut Vec<Attribute>) {
+      attrs.retain(|attr| !attr.path.is_ident("doc"));
+    }
+
+    fn strip_field_docs(fields: &mut Fields) {
+      for field in fields.iter_mut() {
+        strip_docs(&mut field.attrs);
+      }
+    }
+
+    let mut item = parse_str::<Item>(&self.source_code).ok()?;
+
+    match &mut item {
+      Item::Const(i) => strip_docs(&mut i.attrs),
+      Item::Enum(i) => {
+        strip_docs(&mut i.attrs);
+
+        for variant in &mut i.variants {
+          strip_docs(&mut variant.attrs);
+          strip_field_docs(&mut variant.fields);
+        }
+      }
+      Item::Fn(i) => {
+        let mut tokens = i.vis.to_token_stream();
+        i.sig.to_tokens(&mut tokens);
+        return Some(tokens.to_string());
+      }
+      Item::Macro(i) => {
+        return Some(format!("macro_rules! {}", i.ident.as_ref()?));
+      }
+      Item::Mod(i) => {
+        strip_docs(&mut i.attrs);
+        i.content = None;
+        i.semi = Some(Default::default());
+      }
+      Item::Static(i) => strip_docs(&mut i.attrs),
+      Item::Struct(i) => {
+        strip_docs(&mut i.attrs);
+        strip_field_docs(&mut i.fields);
+      }
+      Item::Trait(i) => {
+        strip_docs(&mut i.attrs);
+
+        for item in &mut i.items {
+          match item {
+            TraitItem::Const(i) => strip_docs(&mut i.attrs),
+            TraitItem::Method(i) => {
+              strip_docs(&mut i.attrs);
+              i.default = None;
+              i.semi_token = Some(Default::default());
+            }
+            TraitItem::Type(i) => strip_docs(&mut i.attrs),
+            _ => {}
+          }
+        }
+      }
+      Item::TraitAlias(i) => strip_docs(&mut i.attrs),
+      Item::Type(i) => strip_docs(&mut i.attrs),
+      Item::Union(i) => {
+        strip_docs(&mut i.attrs);
+
+        for field in &mut i.fields.named {
+          strip_docs(&mut field.attrs);
+        }
+      }
+      _ => return None,
+    }
+
+    Some(item.to_token_stream().to_string())
+  }
+
+  pub(crate) fn add_edge(&mut self, target: NodeId, kind: EdgeKind) {
+    if !self
+      .edges
+      .iter()
+      .any(|edge| edge.target == target && edge.kind == kind)
+    {
+      self.edges.push(Edge {
+        target,
+        kind,
+        scope: EdgeScope::default(),
+      });
+    }
+  }
+}
@@ -0,0 +1,229 @@
+use super::*;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum NodeKind {
+  Workspace {
+    path: PathBuf,
+  },
+  Package {
+    path: PathBuf,
+    /// Whether a crate root of the package is `#![no_std]`.
+    no_std: bool,
+    /// The dependencies declared in the package's manifest.
+    dependencies: Vec<ManifestDependency>,
+  },
+  /// A cargo target, such as a library, binary, or example.
+  Target {
+    /// The target's kind, such as `lib`, `bin`, or `example`, or `entry` for
+    /// a module tree analyzed from an arbitrary root file.
+    kind: String,
+    /// The target's root file.
+    path: PathBuf,
+  },
+  Module {
+    path: PathBuf,
+    /// Number of item-position macro invocations, whose items are missing
+    /// from the graph unless macros are expanded.
+    macro_invocations: u32,
+    /// Whether the module contains only `mod` and `use` declarations, like
+    /// a `lib.rs` that just re-exports its submodules.
+    is_facade: bool,
+    /// Dependencies of the package that the module's paths start with,
+    /// such as `axum` in `use axum::Router;`.
+    external_crates: Vec<String>,
+    /// Whether the module's file was larger than [`Config::max_file_size`],
+    /// so it's a placeholder without source code or items.
+    is_skipped: bool,
+  },
+  Struct {
+    fields: Vec<Field>,
+    derives: Vec<String>,
+    /// Marker traits, like `Copy` or `Send`, that the struct derives or that
+    /// the crate implements for it. Auto traits the compiler implements
+    /// implicitly aren't detected, so a missing `Send` doesn't mean the
+    /// struct isn't `Send`.
+    marker_traits: Vec<String>,
+    /// Whether the struct is `#[non_exhaustive]`, so that other crates can't
+    /// construct it with a struct expression.
+    non_exhaustive: bool,
+    /// Whether the struct refers to itself, for example through a `Box`.
+    self_referential: bool,
+  },
+  Enum {
+    variants: Vec<String>,
+    derives: Vec<String>,
+    /// Marker traits that the enum derives or that the crate implements for
+    /// it, with the same limitations as for structs.
+    marker_traits: Vec<String>,
+    /// Whether the enum is `#[non_exhaustive]`, so that other crates must
+    /// match it with a wildcard arm.
+    non_exhaustive: bool,
+    /// Whether the enum refers to itself, for example through a `Box`.
+    self_referential: bool,
+  },
+  Union {
+    fields: Vec<Field>,
+  },
+  Function {
+    arguments: Vec<Field>,
+    return_type: Option<String>,
+    /// Number of `unsafe` blocks in the body.
+    unsafe_block_count: u32,
+    /// Number of `panic!` invocations in the body.
+    panic_count: u32,
+    /// Number of `todo!` invocations in the body.
+    todo_count: u32,
+    /// Number of `unimplemented!` invocations in the body.
+    unimplemented_count: u32,
+    /// Whether the function calls itself, directly or through other
+    /// functions.
+    is_recursive: bool,
+    role: FunctionRole,
+  },
+  Const {
+    ty: String,
+    value: String,
+  },
+  Macro {
+    macro_rules: bool,
+    /// Number of rules in a `macro_rules!` definition.
+    rule_count: u32,
+  },
+  Static {
+    ty: String,
+    mutability: bool,
+  },
+  Trait {
+    is_auto: bool,
+    is_unsafe: bool,
+    /// Whether the trait can be used as `dyn Trait`, as best as can be told
+    /// from its items, or `None` if it can't be.
+    object_safe: Option<bool>,
+    /// Names of types outside the crate that the crate implements the trait
+    /// for.
+    external_implementors: Vec<String>,
+    /// Names of methods with a default body, which implementations inherit
+    /// unless they override them.
+    provided_methods: Vec<String>,
+  },
+  TraitAlias {
+    generics: String,
+  },
+  Type {
+    generics: String,
+  },
+  /// An `impl` block, whose methods and associated consts are its children.
+  Impl {
+    self_type: String,
+    /// The implemented trait, prefixed with `!` for a negative
+    /// implementation, or `None` for an inherent implementation.
+    trait_name: Option<String>,
+    is_unsafe: bool,
+  },
+  /// An item that syn can only represent as tokens, such as one using an
+  /// unstable feature.
+  Verbatim {
+    tokens: String,
+  },
+  /// A stand-in, in a subgraph, for a node outside it that an edge points
+  /// to.
+  External {
+    /// The node's id in the full graph.
+    id: NodeId,
+  },
+  /// A placeholder for the id of a node that a re-analysis removed, so that
+  /// the id isn't reused right away. It isn't the child of any node.
+  Removed,
+  #[default]
+  Unknown,
+}
+
+impl NodeKind {
+  /// Every kind's name, in declaration order.
+  pub const NAMES: &'static [&'static str] = &[
+    "workspace",
+    "package",
+    "target",
+    "module",
+    "struct",
+    "enum",
+    "union",
+    "function",
+    "const",
+    "macro",
+    "static",
+    "trait",
+    "traitAlias",
+    "type",
+    "impl",
+    "verbatim",
+    "external",
+    "removed",
+    "unknown",
+  ];
+
+  /// The kind's name, as it appears in the serialized `type` tag.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Workspace { .. } => "workspace",
+      Self::Package { .. } => "package",
+      Self::Target { .. } => "target",
+      Self::Module { .. } => "module",
+      Self::Struct { .. } => "struct",
+      Self::Enum { .. } => "enum",
+      Self::Union { .. } => "union",
+      Self::Function { .. } => "function",
+      Self::Const { .. } => "const",
+      Self::Macro { .. } => "macro",
+      Self::Static { .. } => "static",
+      Self::Trait { .. } => "trait",
+      Self::TraitAlias { .. } => "traitAlias",
+      Self::Type { .. } => "type",
+      Self::Impl { .. } => "impl",
+      Self::Verbatim { .. } => "verbatim",
+      Self::External { .. } => "external",
+      Self::Removed => "removed",
+      Self::Unknown => "unknown",
+    }
+  }
+
+  /// The namespace that items of this kind are named in, within which names
+  /// in a module must be unique, or `None` for nodes that aren't items.
+  pub(crate) fn namespace(&self) -> Option<&'static str> {
+    match self {
+      Self::Module { .. }
+      | Self::Struct { .. }
+      | Self::Enum { .. }
+      | Self::Union { .. }
+      | Self::Trait { .. }
+      | Self::TraitAlias { .. }
+      | Self::Type { .. } => Some("type"),
+      Self::Function { .. } | Self::Const { .. } | Self::Static { .. } => {
+        Some("value")
+      }
+      Self::Macro { .. } => Some("macro"),
+      _ => None,
+    }
+  }
+
+  /// Check that `name` is the name of a kind.
+  pub fn validate(name: &str) -> Result {
+    if Self::NAMES.contains(&name) {
+      Ok(())
+    } else {
+      Err(anyhow!(
+        "unknown node kind `{name}`, expected one of: {}",
+        Self::NAMES.join(", ")
+      ))
+    }
+  }
+
+  pub(crate) fn discriminant(&self) -> usize {
+    Self::NAMES
+      .iter()
+      .position(|&name| name == self.name())
+      .unwrap()
+  }
+}
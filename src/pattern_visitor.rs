@@ -0,0 +1,22 @@
+use super::*;
+
+/// Collects the names a pattern binds.
+#[derive(Default)]
+pub(crate) struct PatternVisitor {
+  pub(crate) bindings: Vec<String>,
+}
+
+impl PatternVisitor {
+  pub(crate) fn visit(pat: &Pat) -> Vec<String> {
+    let mut visitor = Self::default();
+    visitor.visit_pat(pat);
+    visitor.bindings
+  }
+}
+
+impl<'ast> Visit<'ast> for PatternVisitor {
+  fn visit_pat_ident(&mut self, i: &'ast PatIdent) {
+    self.bindings.push(i.ident.to_string());
+    syn::visit::visit_pat_ident(self, i);
+  }
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+/// One predicate of a `where` clause, such as `T: Clone + 'a`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Predicate {
+  /// The bounded type or lifetime, such as `T`, `Vec<T>`, or `'a`.
+  pub bounded: String,
+  /// Each bound on it, such as `Clone` or `'a`.
+  pub bounds: Vec<String>,
+  /// Paths of the traits among the bounds, without generic arguments, for
+  /// linking to in-crate traits by name.
+  pub traits: Vec<String>,
+  /// The whole predicate, for display, including any higher-ranked `for<'a>`
+  /// binder.
+  pub rendered: String,
+}
+
+impl Predicate {
+  pub(crate) fn new(predicate: &WherePredicate) -> Self {
+    let (bounded, bounds, traits) = match predicate {
+      WherePredicate::Type(predicate) => (
+        render::ty(&predicate.bounded_ty),
+        predicate
+          .bounds
+          .iter()
+          .map(|bound| render::bounds([bound]))
+          .collect(),
+        predicate
+          .bounds
+          .iter()
+          .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => {
+              Some(Analyzer::path_name(&bound.path))
+            }
+            TypeParamBound::Lifetime(_) => None,
+          })
+          .collect(),
+      ),
+      WherePredicate::Lifetime(predicate) => (
+        predicate.lifetime.to_string(),
+        predicate
+          .bounds
+          .iter()
+          .map(|lifetime| lifetime.to_string())
+          .collect(),
+        Vec::new(),
+      ),
+      WherePredicate::Eq(predicate) => (
+        render::ty(&predicate.lhs_ty),
+        vec![render::ty(&predicate.rhs_ty)],
+        Vec::new(),
+      ),
+    };
+
+    Self {
+      bounded,
+      bounds,
+      traits,
+      rendered: render::predicate(predicate),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn multiple_predicates() {
+    let graph = Analyzer::new(&Config::default())
+      .analyze_source(
+        "lib.rs",
+        "
+        fn f<'a, 'b, T>(x: &'a T)
+        where
+          'a: 'b,
+          T: Clone + std::fmt::Debug + 'a,
+          Vec<T>: IntoIterator<Item = T>,
+        {
+        }
+        "
+        .into(),
+      )
+      .unwrap();
+
+    let node = graph.nodes.iter().find(|node| node.name == "f").unwrap();
+
+    assert_eq!(node.lifetimes, ["'a", "'b"]);
+
+    assert_eq!(
+      node.where_clause,
+      [
+        Predicate {
+          bounded: "'a".into(),
+          bounds: vec!["'b".into()],
+          traits: Vec::new(),
+          rendered: "'a: 'b".into(),
+        },
+        Predicate {
+          bounded: "T".into(),
+          bounds: vec!["Clone".into(), "std::fmt::Debug".into(), "'a".into()],
+          traits: vec!["Clone".into(), "std::fmt::Debug".into()],
+          rendered: "T: Clone + std::fmt::Debug + 'a".into(),
+        },
+        Predicate {
+          bounded: "Vec<T>".into(),
+          bounds: vec!["IntoIterator<Item = T>".into()],
+          traits: vec!["IntoIterator".into()],
+          rendered: "Vec<T>: IntoIterator<Item = T>".into(),
+        },
+      ],
+    );
+  }
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+/// Named sets of global options, read from `.eye.toml` in the current
+/// directory and selected with `--profile`:
+///
+/// ```toml
+/// [profiles.architecture]
+/// exclude-kind = ["function", "const", "static"]
+/// no-deps-edges = true
+/// ```
+///
+/// Keys are option names without their leading dashes. `true` passes a flag
+/// and `false` leaves it out, arrays pass an option once per element, and
+/// other values are passed as the option's argument.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Presets {
+  #[serde(default)]
+  profiles: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+impl Presets {
+  const PATH: &'static str = ".eye.toml";
+
+  pub(crate) fn load() -> Result<Self> {
+    let content = fs::read_to_string(Self::PATH)
+      .map_err(|error| anyhow!("failed to read `{}`: {error}", Self::PATH))?;
+
+    toml::from_str(&content)
+      .map_err(|error| anyhow!("failed to parse `{}`: {error}", Self::PATH))
+  }
+
+  /// The command-line arguments that set profile `name`'s options.
+  pub(crate) fn arguments(&self, name: &str) -> Result<Vec<String>> {
+    let Some(profile) = self.profiles.get(name) else {
+      bail!(
+        "unknown profile `{name}`, expected one of: {}",
+        self
+          .profiles
+          .keys()
+          .map(String::as_str)
+          .collect::<Vec<&str>>()
+          .join(", ")
+      );
+    };
+
+    let mut arguments = Vec::new();
+
+    for (option, value) in profile {
+      let values = match value {
+        toml::Value::Array(values) => values.as_slice(),
+        value => slice::from_ref(value),
+      };
+
+      for value in values {
+        match value {
+          toml::Value::Boolean(true) => arguments.push(format!("--{option}")),
+          toml::Value::Boolean(false) => {}
+          toml::Value::String(value) => {
+            arguments.push(format!("--{option}={value}"));
+          }
+          toml::Value::Integer(value) => {
+            arguments.push(format!("--{option}={value}"));
+          }
+          toml::Value::Float(value) => {
+            arguments.push(format!("--{option}={value}"));
+          }
+          _ => bail!(
+            "option `{option}` of profile `{name}` must be a boolean, string, \
+             number, or array of them"
+          ),
+        }
+      }
+    }
+
+    Ok(arguments)
+  }
+}
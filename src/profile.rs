@@ -0,0 +1,32 @@
+use super::*;
+
+/// How long each phase of analysis took.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+  /// Running `cargo metadata`.
+  pub metadata: Duration,
+  /// Per-package phases, in the order packages were analyzed.
+  pub packages: Vec<PackageProfile>,
+  /// Expanding `macro_rules!` invocations.
+  pub expansion: Duration,
+  /// Resolving bounds, derives, and re-exports, and the remaining passes
+  /// over the finished graph.
+  pub resolution: Duration,
+  /// The whole analysis.
+  pub total: Duration,
+}
+
+/// How long each phase of analysis took for a single package.
+#[derive(Clone, Debug, Default)]
+pub struct PackageProfile {
+  pub name: String,
+  /// Finding the package's source files.
+  pub walk: Duration,
+  /// Reading and parsing source files.
+  pub parse: Duration,
+  /// Turning parsed items into nodes.
+  pub items: Duration,
+  /// Linking the package's items to the items they depend on.
+  pub tracing: Duration,
+  pub(crate) id: NodeId,
+}
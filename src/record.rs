@@ -0,0 +1,60 @@
+use super::*;
+
+/// One record of a graph streamed by [`analyze_to_writer`]. A stream holds a
+/// single [`Record::Header`], followed by every [`Record::Node`] in id order,
+/// so that each node follows its parent, then every [`Record::Warning`] in
+/// the order they were encountered, and finally every [`Record::Reexport`],
+/// sorted by path.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum Record {
+  #[serde(rename_all = "camelCase")]
+  Header {
+    /// See [`Graph::schema_version`].
+    schema_version: u32,
+    /// See [`Graph::expanded`].
+    expanded: bool,
+    /// See [`Graph::root`].
+    root: NodeId,
+    /// The number of node records that follow.
+    node_count: usize,
+  },
+  Node(Box<Node>),
+  Warning(Warning),
+  #[serde(rename_all = "camelCase")]
+  Reexport {
+    /// The canonical path of an item reachable from outside its crate.
+    path: String,
+    /// Every public path that names it.
+    public_paths: Vec<String>,
+  },
+}
+
+impl Record {
+  /// Consume `graph`, yielding its records in stream order, so that each
+  /// node is dropped once it has been written.
+  pub(crate) fn stream(graph: Graph) -> impl Iterator<Item = Self> {
+    let Graph {
+      schema_version,
+      expanded,
+      root,
+      nodes,
+      warnings,
+      reexports,
+    } = graph;
+
+    iter::once(Self::Header {
+      schema_version,
+      expanded,
+      root,
+      node_count: nodes.len(),
+    })
+    .chain(nodes.into_iter().map(|node| Self::Node(Box::new(node))))
+    .chain(warnings.into_iter().map(Self::Warning))
+    .chain(
+      reexports
+        .into_iter()
+        .map(|(path, public_paths)| Self::Reexport { path, public_paths }),
+    )
+  }
+}
@@ -0,0 +1,310 @@
+use super::*;
+
+/// A name or glob brought into scope by a `use` declaration.
+#[derive(Clone, Debug)]
+pub(crate) struct Import {
+  /// The name the import binds, or `None` for a glob.
+  name: Option<String>,
+  path: Vec<String>,
+  public: bool,
+}
+
+impl Import {
+  /// Flatten a `use` declaration into one import per name or glob.
+  pub(crate) fn flatten(item: &ItemUse) -> Vec<Self> {
+    let mut imports = Vec::new();
+
+    Self::flatten_tree(
+      &item.tree,
+      Vec::new(),
+      Visibility::from(&item.vis) == Visibility::Public,
+      &mut imports,
+    );
+
+    imports
+  }
+
+  fn flatten_tree(
+    tree: &UseTree,
+    mut path: Vec<String>,
+    public: bool,
+    imports: &mut Vec<Self>,
+  ) {
+    match tree {
+      UseTree::Path(tree) => {
+        path.push(tree.ident.to_string());
+        Self::flatten_tree(&tree.tree, path, public, imports);
+      }
+      UseTree::Name(tree) => {
+        // `use a::{self}` imports `a` itself.
+        if tree.ident != "self" {
+          path.push(tree.ident.to_string());
+        }
+
+        if let Some(name) = path.last().cloned() {
+          imports.push(Self {
+            name: Some(name),
+            path,
+            public,
+          });
+        }
+      }
+      UseTree::Rename(tree) => {
+        // `use Trait as _` brings no name into scope.
+        if tree.rename == "_" {
+          return;
+        }
+
+        if tree.ident != "self" {
+          path.push(tree.ident.to_string());
+        }
+
+        imports.push(Self {
+          name: Some(tree.rename.to_string()),
+          path,
+          public,
+        });
+      }
+      UseTree::Glob(_) => imports.push(Self {
+        name: None,
+        path,
+        public,
+      }),
+      UseTree::Group(group) => {
+        for tree in &group.items {
+          Self::flatten_tree(tree, path.clone(), public, imports);
+        }
+      }
+    }
+  }
+}
+
+/// Follows `pub use` declarations to find every public path at which each
+/// item can be named from outside its crate.
+pub(crate) struct Reexports {
+  /// The names defined in each canonical module.
+  children: HashMap<String, BTreeSet<String>>,
+  /// Canonical paths of crate root modules.
+  crates: BTreeSet<String>,
+  /// Imports by the canonical path of the module they appear in.
+  imports: HashMap<String, Vec<Import>>,
+  /// Canonical paths of every item.
+  items: HashSet<String>,
+  /// Canonical paths of every module.
+  modules: HashSet<String>,
+  /// Canonical paths of every `pub` item.
+  public: HashSet<String>,
+}
+
+impl Reexports {
+  pub(crate) fn new(
+    graph: &Graph,
+    imports: Vec<(NodeId, Vec<Import>)>,
+  ) -> Self {
+    let paths = graph.paths();
+
+    let mut reexports = Self {
+      children: HashMap::new(),
+      crates: BTreeSet::new(),
+      imports: HashMap::new(),
+      items: HashSet::new(),
+      modules: HashSet::new(),
+      public: HashSet::new(),
+    };
+
+    for (node, path) in graph.nodes.iter().zip(&paths) {
+      let Some(path) = path else {
+        continue;
+      };
+
+      reexports.items.insert(path.clone());
+
+      if matches!(node.kind, NodeKind::Module { .. }) {
+        reexports.modules.insert(path.clone());
+      }
+
+      if node.visibility == Visibility::Public {
+        reexports.public.insert(path.clone());
+      }
+
+      match path.rsplit_once("::") {
+        Some((parent, name)) => {
+          reexports
+            .children
+            .entry(parent.to_string())
+            .or_default()
+            .insert(name.to_string());
+        }
+        None => {
+          reexports.crates.insert(path.clone());
+        }
+      }
+    }
+
+    for (module_id, module_imports) in imports {
+      if let Some(path) = &paths[module_id] {
+        reexports
+          .imports
+          .entry(path.clone())
+          .or_default()
+          .extend(module_imports);
+      }
+    }
+
+    reexports
+  }
+
+  /// Map each publicly reachable item's canonical path to every public path
+  /// that names it, including its canonical path if that is public.
+  pub(crate) fn collect(&self) -> BTreeMap<String, Vec<String>> {
+    let mut reachable = BTreeMap::<String, BTreeSet<String>>::new();
+
+    for root in &self.crates {
+      self.walk(root, root, &mut vec![root.clone()], &mut reachable);
+    }
+
+    reachable
+      .into_iter()
+      .map(|(item, paths)| (item, paths.into_iter().collect()))
+      .collect()
+  }
+
+  /// Record the public names of `module`, reached at `path`, descending into
+  /// public modules that aren't already on `stack`, so that re-export cycles
+  /// are followed only once.
+  fn walk(
+    &self,
+    path: &str,
+    module: &str,
+    stack: &mut Vec<String>,
+    reachable: &mut BTreeMap<String, BTreeSet<String>>,
+  ) {
+    for (name, target) in self.exports(module, &mut HashSet::new()) {
+      let public_path = format!("{path}::{name}");
+
+      reachable
+        .entry(target.clone())
+        .or_default()
+        .insert(public_path.clone());
+
+      if self.modules.contains(&target) && !stack.contains(&target) {
+        stack.push(target.clone());
+        self.walk(&public_path, &target, stack, reachable);
+        stack.pop();
+      }
+    }
+  }
+
+  /// The names `module` makes public, along with the canonical paths they
+  /// refer to. `globbed` holds modules whose exports are already included,
+  /// so that glob re-export cycles terminate.
+  fn exports(
+    &self,
+    module: &str,
+    globbed: &mut HashSet<String>,
+  ) -> Vec<(String, String)> {
+    if !globbed.insert(module.to_string()) {
+      return Vec::new();
+    }
+
+    let mut exports = self
+      .children
+      .get(module)
+      .into_iter()
+      .flatten()
+      .map(|name| (name.clone(), format!("{module}::{name}")))
+      .filter(|(_, path)| self.public.contains(path))
+      .collect::<Vec<(String, String)>>();
+
+    for import in self.imports.get(module).into_iter().flatten() {
+      if !import.public {
+        continue;
+      }
+
+      let Some(target) = self.resolve(module, &import.path, &mut Vec::new())
+      else {
+        continue;
+      };
+
+      match &import.name {
+        Some(name) => exports.push((name.clone(), target)),
+        None => exports.extend(self.exports(&target, globbed)),
+      }
+    }
+
+    exports
+  }
+
+  /// Resolve `path`, as written in a `use` declaration in `module`, to a
+  /// canonical path.
+  fn resolve(
+    &self,
+    module: &str,
+    path: &[String],
+    stack: &mut Vec<(String, String)>,
+  ) -> Option<String> {
+    let (first, rest) = path.split_first()?;
+
+    let mut current = match first.as_str() {
+      "crate" => module.split("::").next()?.to_string(),
+      "self" => module.to_string(),
+      "super" => module.rsplit_once("::")?.0.to_string(),
+      name => self
+        .lookup(module, name, stack)
+        .or_else(|| self.crates.contains(name).then(|| name.to_string()))?,
+    };
+
+    for segment in rest {
+      current = match segment.as_str() {
+        "self" => current,
+        "super" => current.rsplit_once("::")?.0.to_string(),
+        name => self.lookup(&current, name, stack)?,
+      };
+    }
+
+    Some(current)
+  }
+
+  /// Find what `name` refers to in `module`: an item defined there, or
+  /// something imported there by name or glob. `stack` holds the lookups in
+  /// progress, so that import cycles fail rather than loop.
+  fn lookup(
+    &self,
+    module: &str,
+    name: &str,
+    stack: &mut Vec<(String, String)>,
+  ) -> Option<String> {
+    let path = format!("{module}::{name}");
+
+    if self.items.contains(&path) {
+      return Some(path);
+    }
+
+    let key = (module.to_string(), name.to_string());
+
+    if stack.contains(&key) {
+      return None;
+    }
+
+    stack.push(key);
+
+    let imports = self.imports.get(module).into_iter().flatten();
+
+    let target = imports
+      .clone()
+      .filter(|import| import.name.as_deref() == Some(name))
+      .find_map(|import| self.resolve(module, &import.path, stack))
+      .or_else(|| {
+        imports
+          .filter(|import| import.name.is_none())
+          .find_map(|import| {
+            let glob = self.resolve(module, &import.path, stack)?;
+            self.lookup(&glob, name, stack)
+          })
+      });
+
+    stack.pop();
+
+    target
+  }
+}
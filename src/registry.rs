@@ -0,0 +1,100 @@
+use super::*;
+
+/// Download the source of a published crate, given as `name` or
+/// `name@version`, and return the directory containing its `Cargo.toml`.
+///
+/// Resolution is delegated to cargo: a scratch package depending on the
+/// requested crate is created under the cache directory, and its metadata is
+/// queried, which fetches the crate into cargo's registry cache. Both the
+/// scratch package and its lockfile are kept, so later runs with the same
+/// spec resolve to the same version without refetching.
+pub fn fetch_crate(spec: &str) -> Result<PathBuf> {
+  let (name, version) = match spec.split_once('@') {
+    Some((name, version)) => (name, Some(version)),
+    None => (spec, None),
+  };
+
+  if name.is_empty()
+    || !name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+  {
+    bail!("invalid crate name `{name}`");
+  }
+
+  let requirement = requirement(version)?;
+
+  let scratch = cache_dir()?.join("crates").join(
+    spec
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+      .collect::<String>(),
+  );
+
+  fs::create_dir_all(&scratch)?;
+
+  fs::write(
+    scratch.join("Cargo.toml"),
+    format!(
+      "[package]\nname = \"eye-fetch\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[lib]\npath = \"lib.rs\"\n\n[dependencies]\n{name} = \"{requirement}\"\n\n[workspace]\n"
+    ),
+  )?;
+
+  fs::write(scratch.join("lib.rs"), "")?;
+
+  let metadata = MetadataCommand::new()
+    .manifest_path(scratch.join("Cargo.toml"))
+    .exec()
+    .map_err(|error| anyhow!("failed to fetch crate `{spec}`: {error}"))?;
+
+  let package = metadata
+    .packages
+    .iter()
+    .find(|package| package.name.replace('-', "_") == name.replace('-', "_"))
+    .ok_or_else(|| anyhow!("crate `{name}` not found in registry"))?;
+
+  Ok(package.manifest_path.parent().unwrap().into())
+}
+
+/// The version requirement for a spec's version: an exact match for a bare
+/// version like `1.2.3`, any version if there's none, and otherwise the
+/// requirement as given, such as `^1.2`. Parsing it, rather than pasting it
+/// into the scratch manifest, keeps quotes and newlines out of the manifest.
+fn requirement(version: Option<&str>) -> Result<VersionReq> {
+  let requirement = match version {
+    Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+      format!("={version}")
+    }
+    Some(version) => version.to_owned(),
+    None => "*".into(),
+  };
+
+  VersionReq::parse(&requirement).map_err(|error| {
+    anyhow!("invalid version requirement `{requirement}`: {error}")
+  })
+}
+
+fn cache_dir() -> Result<PathBuf> {
+  if let Some(dir) = env::var_os("XDG_CACHE_HOME") {
+    return Ok(PathBuf::from(dir).join("eye"));
+  }
+
+  match env::var_os("HOME") {
+    Some(home) => Ok(PathBuf::from(home).join(".cache").join("eye")),
+    None => bail!("could not determine cache directory"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn requirements() {
+    assert_eq!(requirement(None).unwrap().to_string(), "*");
+    assert_eq!(requirement(Some("1.2.3")).unwrap().to_string(), "=1.2.3");
+    assert_eq!(requirement(Some("^1.2")).unwrap().to_string(), "^1.2");
+    assert!(requirement(Some("1\"\n[patch]")).is_err());
+    assert!(requirement(Some("\"")).is_err());
+  }
+}
@@ -0,0 +1,445 @@
+use super::*;
+
+/// Render a type the way rustdoc prints it, such as `Vec<&'a mut T>`, rather
+/// than with spaces between every token, such as `Vec < & 'a mut T >`, as
+/// stringifying tokens does. Expressions, such as array lengths, are still
+/// stringified from tokens.
+pub(crate) fn ty(ty: &Type) -> String {
+  match ty {
+    Type::Array(array) => {
+      format!("[{}; {}]", self::ty(&array.elem), tokens(&array.len))
+    }
+    Type::BareFn(bare_fn) => {
+      let mut rendered = String::new();
+
+      if let Some(lifetimes) = &bare_fn.lifetimes {
+        rendered.push_str(&bound_lifetimes(lifetimes));
+      }
+
+      if bare_fn.unsafety.is_some() {
+        rendered.push_str("unsafe ");
+      }
+
+      if let Some(abi) = &bare_fn.abi {
+        rendered.push_str(&abi_prefix(abi));
+      }
+
+      let mut inputs = bare_fn
+        .inputs
+        .iter()
+        .map(|input| match &input.name {
+          Some((name, _)) => format!("{name}: {}", self::ty(&input.ty)),
+          None => self::ty(&input.ty),
+        })
+        .collect::<Vec<String>>();
+
+      if bare_fn.variadic.is_some() {
+        inputs.push("...".into());
+      }
+
+      rendered.push_str(&format!(
+        "fn({}){}",
+        inputs.join(", "),
+        return_type(&bare_fn.output)
+      ));
+
+      rendered
+    }
+    Type::Group(group) => self::ty(&group.elem),
+    Type::ImplTrait(impl_trait) => {
+      format!("impl {}", bounds(&impl_trait.bounds))
+    }
+    Type::Infer(_) => "_".into(),
+    Type::Macro(mac) => tokens(mac),
+    Type::Never(_) => "!".into(),
+    Type::Paren(paren) => format!("({})", self::ty(&paren.elem)),
+    Type::Path(type_path) => match &type_path.qself {
+      Some(qself) => {
+        let segments = type_path
+          .path
+          .segments
+          .iter()
+          .map(segment)
+          .collect::<Vec<String>>();
+
+        let (trait_segments, rest) = segments.split_at(qself.position);
+
+        let mut rendered = match trait_segments {
+          [] => format!("<{}>", self::ty(&qself.ty)),
+          _ => format!(
+            "<{} as {}{}>",
+            self::ty(&qself.ty),
+            if type_path.path.leading_colon.is_some() {
+              "::"
+            } else {
+              ""
+            },
+            trait_segments.join("::")
+          ),
+        };
+
+        for segment in rest {
+          rendered.push_str("::");
+          rendered.push_str(segment);
+        }
+
+        rendered
+      }
+      None => path(&type_path.path),
+    },
+    Type::Ptr(ptr) => format!(
+      "*{} {}",
+      if ptr.mutability.is_some() {
+        "mut"
+      } else {
+        "const"
+      },
+      self::ty(&ptr.elem)
+    ),
+    Type::Reference(reference) => {
+      let mut rendered = "&".to_string();
+
+      if let Some(lifetime) = &reference.lifetime {
+        rendered.push_str(&format!("{lifetime} "));
+      }
+
+      if reference.mutability.is_some() {
+        rendered.push_str("mut ");
+      }
+
+      rendered.push_str(&self::ty(&reference.elem));
+
+      rendered
+    }
+    Type::Slice(slice) => format!("[{}]", self::ty(&slice.elem)),
+    Type::TraitObject(trait_object) => match trait_object.dyn_token {
+      Some(_) => format!("dyn {}", bounds(&trait_object.bounds)),
+      None => bounds(&trait_object.bounds),
+    },
+    Type::Tuple(tuple) => match tuple.elems.len() {
+      1 => format!("({},)", self::ty(&tuple.elems[0])),
+      _ => format!(
+        "({})",
+        tuple
+          .elems
+          .iter()
+          .map(self::ty)
+          .collect::<Vec<String>>()
+          .join(", ")
+      ),
+    },
+    ty => tokens(ty),
+  }
+}
+
+/// Render a function's return type, including the arrow, or nothing if it
+/// returns `()` implicitly.
+pub(crate) fn return_type(output: &ReturnType) -> String {
+  match output {
+    ReturnType::Default => String::new(),
+    ReturnType::Type(_, ty) => format!(" -> {}", self::ty(ty)),
+  }
+}
+
+/// Render a list of generic parameters, with their bounds and defaults, or
+/// nothing if there are none.
+pub(crate) fn generics(generics: &Generics) -> String {
+  if generics.params.is_empty() {
+    return String::new();
+  }
+
+  let params = generics
+    .params
+    .iter()
+    .map(|param| match param {
+      GenericParam::Type(param) => {
+        let mut rendered = param.ident.to_string();
+
+        if !param.bounds.is_empty() {
+          rendered.push_str(&format!(": {}", bounds(&param.bounds)));
+        }
+
+        if let Some(default) = &param.default {
+          rendered.push_str(&format!(" = {}", ty(default)));
+        }
+
+        rendered
+      }
+      GenericParam::Lifetime(param) => {
+        let mut rendered = param.lifetime.to_string();
+
+        if !param.bounds.is_empty() {
+          rendered.push_str(&format!(
+            ": {}",
+            param
+              .bounds
+              .iter()
+              .map(|lifetime| lifetime.to_string())
+              .collect::<Vec<String>>()
+              .join(" + ")
+          ));
+        }
+
+        rendered
+      }
+      GenericParam::Const(param) => {
+        let mut rendered = format!("const {}: {}", param.ident, ty(&param.ty));
+
+        if let Some(default) = &param.default {
+          rendered.push_str(&format!(" = {}", tokens(default)));
+        }
+
+        rendered
+      }
+    })
+    .collect::<Vec<String>>();
+
+  format!("<{}>", params.join(", "))
+}
+
+/// Render trait and lifetime bounds, separated by `+`.
+pub(crate) fn bounds<'a>(
+  bounds: impl IntoIterator<Item = &'a TypeParamBound>,
+) -> String {
+  bounds
+    .into_iter()
+    .map(|bound| match bound {
+      TypeParamBound::Trait(bound) => {
+        let mut rendered = String::new();
+
+        if let Some(lifetimes) = &bound.lifetimes {
+          rendered.push_str(&bound_lifetimes(lifetimes));
+        }
+
+        if let TraitBoundModifier::Maybe(_) = bound.modifier {
+          rendered.push('?');
+        }
+
+        rendered.push_str(&path(&bound.path));
+
+        match bound.paren_token {
+          Some(_) => format!("({rendered})"),
+          None => rendered,
+        }
+      }
+      TypeParamBound::Lifetime(lifetime) => lifetime.to_string(),
+    })
+    .collect::<Vec<String>>()
+    .join(" + ")
+}
+
+/// Render a `where` clause predicate, such as `for<'a> F: Fn(&'a str)` or
+/// `'a: 'b`.
+pub(crate) fn predicate(predicate: &WherePredicate) -> String {
+  match predicate {
+    WherePredicate::Type(predicate) => {
+      let mut rendered = String::new();
+
+      if let Some(lifetimes) = &predicate.lifetimes {
+        rendered.push_str(&bound_lifetimes(lifetimes));
+      }
+
+      rendered.push_str(&format!(
+        "{}: {}",
+        ty(&predicate.bounded_ty),
+        bounds(&predicate.bounds)
+      ));
+
+      rendered
+    }
+    WherePredicate::Lifetime(predicate) => format!(
+      "{}: {}",
+      predicate.lifetime,
+      predicate
+        .bounds
+        .iter()
+        .map(|lifetime| lifetime.to_string())
+        .collect::<Vec<String>>()
+        .join(" + ")
+    ),
+    WherePredicate::Eq(predicate) => {
+      format!("{} = {}", ty(&predicate.lhs_ty), ty(&predicate.rhs_ty))
+    }
+  }
+}
+
+/// Render a path, such as `std::collections::HashMap<K, V>`.
+pub(crate) fn path(path: &syn::Path) -> String {
+  let segments = path
+    .segments
+    .iter()
+    .map(segment)
+    .collect::<Vec<String>>()
+    .join("::");
+
+  match path.leading_colon {
+    Some(_) => format!("::{segments}"),
+    None => segments,
+  }
+}
+
+fn segment(segment: &PathSegment) -> String {
+  let ident = segment.ident.to_string();
+
+  match &segment.arguments {
+    PathArguments::None => ident,
+    PathArguments::AngleBracketed(arguments) => format!(
+      "{ident}{}<{}>",
+      if arguments.colon2_token.is_some() {
+        "::"
+      } else {
+        ""
+      },
+      arguments
+        .args
+        .iter()
+        .map(|argument| match argument {
+          GenericArgument::Lifetime(lifetime) => lifetime.to_string(),
+          GenericArgument::Type(argument) => ty(argument),
+          GenericArgument::Binding(binding) => {
+            format!("{} = {}", binding.ident, ty(&binding.ty))
+          }
+          GenericArgument::Constraint(constraint) => {
+            format!("{}: {}", constraint.ident, bounds(&constraint.bounds))
+          }
+          GenericArgument::Const(expr) => tokens(expr),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+    ),
+    PathArguments::Parenthesized(arguments) => format!(
+      "{ident}({}){}",
+      arguments
+        .inputs
+        .iter()
+        .map(ty)
+        .collect::<Vec<String>>()
+        .join(", "),
+      return_type(&arguments.output)
+    ),
+  }
+}
+
+/// Render a higher-ranked `for<'a>` binder, with a trailing space.
+fn bound_lifetimes(lifetimes: &BoundLifetimes) -> String {
+  format!(
+    "for<{}> ",
+    lifetimes
+      .lifetimes
+      .iter()
+      .map(|lifetime| lifetime.lifetime.to_string())
+      .collect::<Vec<String>>()
+      .join(", ")
+  )
+}
+
+/// Render an `extern` ABI, with a trailing space.
+fn abi_prefix(abi: &Abi) -> String {
+  match &abi.name {
+    Some(name) => format!("extern {} ", name.to_token_stream()),
+    None => "extern ".into(),
+  }
+}
+
+/// Stringify anything without a dedicated renderer from its tokens.
+fn tokens(tokens: &impl ToTokens) -> String {
+  tokens.to_token_stream().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(source: &str, expected: &str) {
+    assert_eq!(ty(&syn::parse_str(source).unwrap()), expected);
+  }
+
+  #[test]
+  fn types() {
+    case("Vec < String >", "Vec<String>");
+    case("& 'a mut T", "&'a mut T");
+    case("& mut T", "&mut T");
+    case("* const u8", "*const u8");
+    case("[u8 ; 4]", "[u8; 4]");
+    case("[T]", "[T]");
+    case("(A , B)", "(A, B)");
+    case("(A ,)", "(A,)");
+    case("()", "()");
+    case("!", "!");
+    case("_", "_");
+    case("dyn Trait + Send", "dyn Trait + Send");
+    case(
+      "Box < dyn Fn (& str) -> bool + 'static >",
+      "Box<dyn Fn(&str) -> bool + 'static>",
+    );
+    case("impl Iterator < Item = u8 >", "impl Iterator<Item = u8>");
+    case(
+      ":: std :: collections :: HashMap < K , V >",
+      "::std::collections::HashMap<K, V>",
+    );
+    case("< T as Iterator > :: Item", "<T as Iterator>::Item");
+    case("< T > :: Item", "<T>::Item");
+    case("Vec :: < T >", "Vec::<T>");
+  }
+
+  #[test]
+  fn function_pointers() {
+    case("fn (u8) -> u8", "fn(u8) -> u8");
+    case(
+      "unsafe extern \"C\" fn (x : i32 , ...)",
+      "unsafe extern \"C\" fn(x: i32, ...)",
+    );
+    case("for < 'a > fn (& 'a str)", "for<'a> fn(&'a str)");
+  }
+
+  #[test]
+  fn generic_parameters() {
+    let item = syn::parse_str::<syn::ItemStruct>(
+      "struct S<'a: 'b, 'b, T: Clone + ?Sized = u8, const N: usize = 4>;",
+    )
+    .unwrap();
+
+    assert_eq!(
+      generics(&item.generics),
+      "<'a: 'b, 'b, T: Clone + ?Sized = u8, const N: usize = 4>",
+    );
+  }
+
+  #[test]
+  fn where_predicates() {
+    let item = syn::parse_str::<syn::ItemFn>(
+      "fn f<'a, 'b, F, T>() where 'a: 'b, for<'c> F: Fn(&'c T) -> &'c str, \
+       Vec<T>: Into<Box<[T]>> {}",
+    )
+    .unwrap();
+
+    assert_eq!(
+      item
+        .sig
+        .generics
+        .where_clause
+        .unwrap()
+        .predicates
+        .iter()
+        .map(predicate)
+        .collect::<Vec<String>>(),
+      [
+        "'a: 'b",
+        "for<'c> F: Fn(&'c T) -> &'c str",
+        "Vec<T>: Into<Box<[T]>>",
+      ],
+    );
+  }
+
+  #[test]
+  fn return_types() {
+    let item = syn::parse_str::<syn::ItemFn>("fn f() {}").unwrap();
+    assert_eq!(return_type(&item.sig.output), "");
+
+    let item =
+      syn::parse_str::<syn::ItemFn>("fn f() -> Result < (), String > {}")
+        .unwrap();
+    assert_eq!(return_type(&item.sig.output), " -> Result<(), String>");
+  }
+}
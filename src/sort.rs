@@ -0,0 +1,13 @@
+use super::*;
+
+/// The order in which a node's children appear.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Sort {
+  /// The order items are declared in, following the source walk.
+  #[default]
+  Source,
+  /// Alphabetically by name.
+  Name,
+  /// Grouped by kind, in the order kinds are declared in `NodeKind`.
+  Kind,
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+/// The encoding of records written by [`analyze_to_writer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum StreamFormat {
+  /// Newline-delimited JSON, one record per line.
+  #[default]
+  Ndjson,
+  /// Concatenated MessagePack maps, one per record.
+  Msgpack,
+}
+
+impl StreamFormat {
+  pub(crate) fn write(
+    self,
+    writer: &mut impl Write,
+    record: &Record,
+  ) -> Result {
+    match self {
+      Self::Ndjson => {
+        serde_json::to_writer(&mut *writer, record)?;
+        writer.write_all(b"\n")?;
+      }
+      Self::Msgpack => rmp_serde::encode::write_named(writer, record)?,
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,53 @@
+use {
+  self::{
+    bench::Bench, export::Export, list::List, public_api::PublicApi,
+    serve_file::ServeFile, server::Server, stats::Stats, validate::Validate,
+  },
+  super::*,
+};
+
+mod bench;
+mod export;
+mod list;
+mod public_api;
+mod serve_file;
+mod server;
+mod stats;
+mod validate;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Subcommand {
+  /// Time each phase of analysis, without serving
+  Bench(Bench),
+  /// Write the graph to standard output
+  Export(Export),
+  /// Print one line per item: path, kind, visibility, and location
+  List(List),
+  /// Print the path, kind, and signature of each item reachable from
+  /// outside the crate, sorted by path
+  PublicApi(PublicApi),
+  /// Serve the graph over HTTP
+  Serve(Server),
+  /// Serve a graph previously written by `export`, without analyzing
+  ServeFile(ServeFile),
+  /// Print summary statistics about the crate
+  Stats(Stats),
+  /// Check that every source file can be analyzed, without printing the
+  /// graph
+  Validate(Validate),
+}
+
+impl Subcommand {
+  pub(crate) async fn run(self, options: Options) -> Result {
+    match self {
+      Subcommand::Bench(bench) => bench.run(options),
+      Subcommand::Export(export) => export.run(options),
+      Subcommand::List(list) => list.run(options),
+      Subcommand::PublicApi(public_api) => public_api.run(options),
+      Subcommand::Serve(server) => server.run(options).await,
+      Subcommand::ServeFile(serve_file) => serve_file.run(options).await,
+      Subcommand::Stats(stats) => stats.run(options),
+      Subcommand::Validate(validate) => validate.run(options),
+    }
+  }
+}
@@ -0,0 +1,50 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bench {}
+
+impl Bench {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let (graph, profile) =
+      eye::profile(options.crate_path()?, &options.config())?;
+
+    println!("{} {:?}", options.color.bold("Metadata:"), profile.metadata);
+
+    for package in &profile.packages {
+      println!(
+        "{}",
+        options.color.bold(format!("Package {}:", package.name))
+      );
+      println!("  Walk: {:?}", package.walk);
+      println!("  Parse: {:?}", package.parse);
+      println!("  Items: {:?}", package.items);
+      println!("  Tracing: {:?}", package.tracing);
+    }
+
+    println!(
+      "{} {:?}",
+      options.color.bold("Expansion:"),
+      profile.expansion
+    );
+    println!(
+      "{} {:?}",
+      options.color.bold("Resolution:"),
+      profile.resolution
+    );
+    println!("{} {:?}", options.color.bold("Total:"), profile.total);
+
+    println!("{} {}", options.color.bold("Nodes:"), graph.nodes.len());
+
+    println!(
+      "{} {}",
+      options.color.bold("Edges:"),
+      graph
+        .nodes
+        .iter()
+        .map(|node| node.edges.len())
+        .sum::<usize>()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Export {
+  /// Serialization format
+  #[clap(long, value_enum, default_value_t = Format::Json)]
+  format: Format,
+  /// Number of spaces to indent `json-pretty` output by
+  #[clap(long, value_name = "N", default_value_t = 2)]
+  indent: usize,
+}
+
+impl Export {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let graph = options.analyze(&options.config())?;
+
+    let body = match self.format {
+      Format::JsonPretty => Format::pretty(&graph, self.indent)?,
+      Format::Matrix => Format::matrix(&graph),
+      format => format.serialize(&graph)?,
+    };
+
+    io::stdout().write_all(&body)?;
+
+    Ok(())
+  }
+}
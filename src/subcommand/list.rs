@@ -0,0 +1,82 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct List {
+  /// Only list items of this kind
+  #[clap(
+    long = "kind",
+    value_name = "KIND",
+    value_parser = PossibleValuesParser::new(NodeKind::NAMES)
+  )]
+  kinds: Vec<String>,
+  /// Only list `pub` items
+  #[clap(long)]
+  public_only: bool,
+  /// Only list items reachable from outside the crate, by a public path or
+  /// through the signature of another reachable item
+  #[clap(long)]
+  reachable_only: bool,
+  /// With `--public-only`, include items marked `#[doc(hidden)]`, and items
+  /// inside them
+  #[clap(long, requires = "public_only")]
+  include_doc_hidden: bool,
+}
+
+impl List {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let graph = options.analyze(&options.config())?;
+
+    let root = options
+      .crate_path
+      .as_deref()
+      .map(|crate_path| crate_path.canonicalize())
+      .transpose()?
+      .unwrap_or_default();
+
+    let paths = graph.paths();
+
+    let hidden = graph.hidden();
+
+    let modules = graph.nearest(|kind| matches!(kind, NodeKind::Module { .. }));
+
+    let mut lines = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        self.kinds.is_empty()
+          || self.kinds.iter().any(|kind| kind == node.kind.name())
+      })
+      .filter(|node| !self.reachable_only || node.publicly_reachable)
+      .filter(|node| {
+        !self.public_only
+          || node.visibility == Visibility::Public
+            && (self.include_doc_hidden || !hidden[node.id])
+      })
+      .filter_map(|node| {
+        let path = paths[node.id].as_ref()?;
+
+        let NodeKind::Module { path: file, .. } =
+          &graph.nodes[modules[node.id]?].kind
+        else {
+          return None;
+        };
+
+        Some((
+          path,
+          node.kind.name(),
+          node.visibility.name(),
+          file.strip_prefix(&root).unwrap_or(file),
+          node.line.unwrap_or(1),
+        ))
+      })
+      .collect::<Vec<_>>();
+
+    lines.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (path, kind, visibility, file, line) in lines {
+      println!("{path}\t{kind}\t{visibility}\t{}:{line}", file.display());
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct PublicApi {
+  /// Include items marked `#[doc(hidden)]`, and items inside them, which are
+  /// left out by default, as rustdoc does
+  #[clap(long)]
+  include_doc_hidden: bool,
+}
+
+impl PublicApi {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let graph = options.analyze(&options.config())?;
+
+    let paths = graph.paths();
+
+    let hidden = graph.hidden();
+
+    let mut items = BTreeSet::new();
+
+    for node in &graph.nodes {
+      if hidden[node.id] && !self.include_doc_hidden {
+        continue;
+      }
+
+      let Some(public_paths) = paths[node.id]
+        .as_ref()
+        .and_then(|path| graph.reexports.get(path))
+      else {
+        continue;
+      };
+
+      let Some(signature) = node.signature() else {
+        continue;
+      };
+
+      for path in public_paths {
+        items.insert((path, node.kind.name(), signature.clone()));
+      }
+    }
+
+    for (path, kind, signature) in items {
+      println!("{path}\t{kind}\t{signature}");
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct ServeFile {
+  /// Graph previously written by `export --format json`
+  #[clap(value_name = "PATH")]
+  path: PathBuf,
+  #[clap(flatten)]
+  server: Server,
+}
+
+impl ServeFile {
+  pub(crate) async fn run(self, options: Options) -> Result {
+    let json = fs::read_to_string(&self.path)
+      .with_context(|| format!("failed to read `{}`", self.path.display()))?;
+
+    let value = serde_json::from_str::<Value>(&json).map_err(|error| {
+      anyhow!("`{}` is not valid JSON: {error}", self.path.display())
+    })?;
+
+    let schema_version = value
+      .get("schemaVersion")
+      .and_then(Value::as_u64)
+      .unwrap_or_default();
+
+    if schema_version != u64::from(Graph::SCHEMA_VERSION) {
+      bail!(
+        "`{}` has graph schema version {schema_version}, but this version of \
+         eye serves version {}",
+        self.path.display(),
+        Graph::SCHEMA_VERSION
+      );
+    }
+
+    let graph = serde_json::from_value::<Graph>(value).map_err(|error| {
+      anyhow!("`{}` is not a valid graph: {error}", self.path.display())
+    })?;
+
+    self.server.serve(options, Some(graph)).await
+  }
+}
@@ -0,0 +1,1145 @@
+use super::*;
+
+const STREAM_BUFFER: usize = 64;
+
+const X_EYE_ANALYSIS_CACHED: HeaderName =
+  HeaderName::from_static("x-eye-analysis-cached");
+
+const X_EYE_BUILD_DURATION_MS: HeaderName =
+  HeaderName::from_static("x-eye-build-duration-ms");
+
+const X_EYE_NODE_COUNT: HeaderName =
+  HeaderName::from_static("x-eye-node-count");
+
+#[derive(Debug, Parser)]
+pub(crate) struct Server {
+  #[clap(short, long, default_value = "8000")]
+  port: u16,
+  /// Serve every route under this path prefix, such as `/eye` for
+  /// `/eye/api/graph`, for hosting behind a reverse proxy
+  #[clap(
+    long,
+    value_name = "PREFIX",
+    default_value = "",
+    value_parser = Self::base_path
+  )]
+  base_path: String,
+  /// Listen on a Unix domain socket at this path, rather than on a TCP
+  /// port, removing it on shutdown
+  #[clap(long, value_name = "PATH", conflicts_with = "port")]
+  unix_socket: Option<PathBuf>,
+  /// Run at most this many analyses at once, with further requests waiting
+  /// for one to finish, and reusing its graph if they ask for the same one
+  #[clap(
+    long,
+    value_name = "N",
+    default_value_t = 1,
+    value_parser = clap::value_parser!(u32).range(1..)
+  )]
+  concurrency: u32,
+  /// While every analysis slot is busy, respond with the last graph
+  /// analyzed with the same settings, if any, rather than waiting
+  #[clap(long)]
+  serve_stale: bool,
+  /// Truncate `/api/graph` responses larger than this, keeping the nodes
+  /// nearest the root
+  #[clap(long, value_name = "BYTES")]
+  max_response_bytes: Option<usize>,
+  /// Close HTTP/1.1 connections after each response, rather than keeping
+  /// them open for further requests
+  #[clap(long)]
+  no_keep_alive: bool,
+  /// Close HTTP/1.1 connections whose request headers take longer than this
+  /// to arrive
+  #[clap(long, value_name = "SECONDS", default_value_t = 30)]
+  header_read_timeout: u64,
+  /// Ping idle HTTP/2 connections this often to check that they're alive
+  #[clap(long, value_name = "SECONDS", default_value_t = 20)]
+  keep_alive_interval: u64,
+  /// Close HTTP/2 connections that don't answer a ping within this long
+  #[clap(long, value_name = "SECONDS", default_value_t = 20)]
+  keep_alive_timeout: u64,
+}
+
+/// State shared by every request handler.
+struct Context {
+  /// Permits to analyze, one per `--concurrency`.
+  analyses: Semaphore,
+  /// The settings, as debug-formatted, the time, and the result of the
+  /// latest analysis to finish.
+  latest: Mutex<Option<(String, Instant, Graph)>>,
+  max_response_bytes: Option<usize>,
+  options: Options,
+  serve_stale: bool,
+  /// A previously exported graph to serve instead of analyzing.
+  snapshot: Option<Graph>,
+}
+
+/// A graph cut down to fit within `--max-response-bytes`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Truncated<T> {
+  #[serde(flatten)]
+  graph: T,
+  truncated: bool,
+  total_nodes: usize,
+}
+
+/// What a `/api/graph` response includes of each node.
+enum Shape {
+  Full,
+  /// Only what [`Server::skeleton`] keeps.
+  Skeleton,
+  /// Only the `?fields=` named.
+  Fields(Vec<String>),
+}
+
+impl Server {
+  pub(crate) async fn run(self, options: Options) -> Result {
+    self.serve(options, None).await
+  }
+
+  /// Serve `snapshot`, if given, or else the graph of the crate or file
+  /// `options` name, analyzed afresh for each request.
+  pub(crate) async fn serve(
+    self,
+    options: Options,
+    snapshot: Option<Graph>,
+  ) -> Result {
+    let state = Arc::new(Context {
+      analyses: Semaphore::new(self.concurrency.try_into().unwrap()),
+      latest: Mutex::new(None),
+      max_response_bytes: self.max_response_bytes,
+      options,
+      serve_stale: self.serve_stale,
+      snapshot,
+    });
+
+    let router = Router::new()
+      .route("/api/graph", get(Self::graph))
+      .route("/api/graph/stream", get(Self::stream))
+      .route("/api/implementors/:id", get(Self::implementors))
+      .route("/api/kinds", get(Self::kinds))
+      .route("/api/legend", get(Self::legend))
+      .route("/api/meta", get(Self::meta))
+      .route("/api/methods/:id", get(Self::methods))
+      .route("/api/node/:id", get(Self::node))
+      .route("/api/package/:id/dependencies", get(Self::dependencies))
+      .route("/api/package-graph", get(Self::package_graph))
+      .route("/api/children/:id", get(Self::children))
+      .route("/api/subtree/:id", get(Self::subtree))
+      .route("/api/reexports", get(Self::reexports))
+      .route("/api/search", get(Self::search))
+      .route("/api/warnings", get(Self::warnings))
+      .layer(middleware::map_response(Self::json_error))
+      .with_state(state);
+
+    let router = match self.base_path.as_str() {
+      "" => router,
+      base_path => {
+        info!("Serving under base path: {base_path}");
+        Router::new().nest(base_path, router)
+      }
+    }
+    .layer(CorsLayer::permissive());
+
+    // Each connection speaks HTTP/1.1 or, with prior knowledge, HTTP/2,
+    // depending on its preface.
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+
+    builder
+      .http1()
+      .keep_alive(!self.no_keep_alive)
+      .header_read_timeout(Duration::from_secs(self.header_read_timeout))
+      .timer(TokioTimer::new());
+
+    builder
+      .http2()
+      .keep_alive_interval(Duration::from_secs(self.keep_alive_interval))
+      .keep_alive_timeout(Duration::from_secs(self.keep_alive_timeout))
+      .timer(TokioTimer::new());
+
+    let Some(path) = self.unix_socket else {
+      let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
+
+      info!("Listening on port: {}", addr.port());
+
+      let listener = TcpListener::bind(addr).await?;
+
+      loop {
+        match listener.accept().await {
+          Ok((stream, _)) => Self::spawn_connection(&builder, &router, stream),
+          Err(error) => Self::accept_failed(error).await,
+        }
+      }
+    };
+
+    // A socket left behind by a server that didn't shut down cleanly would
+    // make binding fail.
+    if fs::symlink_metadata(&path)
+      .is_ok_and(|metadata| metadata.file_type().is_socket())
+    {
+      fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path).map_err(|error| {
+      anyhow!("failed to bind Unix socket `{}`: {error}", path.display())
+    })?;
+
+    info!("Listening on Unix socket: {}", path.display());
+
+    loop {
+      tokio::select! {
+        accepted = listener.accept() => match accepted {
+          Ok((stream, _)) => Self::spawn_connection(&builder, &router, stream),
+          Err(error) => Self::accept_failed(error).await,
+        },
+        _ = signal::ctrl_c() => break,
+      }
+    }
+
+    fs::remove_file(&path)?;
+
+    Ok(())
+  }
+
+  /// Log a failure to accept a connection, such as from running out of file
+  /// descriptors or a client resetting before it was accepted, and pause
+  /// briefly before accepting more, rather than stopping the server or
+  /// spinning while the condition lasts.
+  async fn accept_failed(error: io::Error) {
+    error!("Error accepting connection: {error}");
+    time::sleep(Duration::from_millis(100)).await;
+  }
+
+  /// Wrap a plain-text error response, whether from a handler or from an
+  /// extractor rejecting the request, in a JSON object with the message as
+  /// its `error` field, since `/api` clients parse every response as JSON.
+  async fn json_error(response: Response) -> Response {
+    let status = response.status();
+
+    let is_text = response
+      .headers()
+      .get(CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .is_some_and(|content_type| content_type.starts_with("text/plain"));
+
+    if !(status.is_client_error() || status.is_server_error()) || !is_text {
+      return response;
+    }
+
+    let message = match body::to_bytes(response.into_body(), usize::MAX).await {
+      Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+      Err(error) => error.to_string(),
+    };
+
+    (status, Json(json!({ "error": message }))).into_response()
+  }
+
+  /// Normalize a `--base-path` to start with a slash and not end with one,
+  /// so that `eye/`, `/eye`, and `/eye/` are all `/eye`, and `/` is no
+  /// prefix at all.
+  fn base_path(prefix: &str) -> Result<String, Infallible> {
+    let prefix = prefix.trim_matches('/');
+
+    Ok(if prefix.is_empty() {
+      String::new()
+    } else {
+      format!("/{prefix}")
+    })
+  }
+
+  /// Serve HTTP requests arriving on `stream` on their own task.
+  fn spawn_connection(
+    builder: &auto::Builder<TokioExecutor>,
+    router: &Router,
+    stream: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+  ) {
+    let builder = builder.clone();
+
+    let service = TowerToHyperService::new(router.clone());
+
+    tokio::spawn(async move {
+      if let Err(error) = builder
+        .serve_connection_with_upgrades(TokioIo::new(stream), service)
+        .await
+      {
+        error!("Error serving connection: {error}");
+      }
+    });
+  }
+
+  /// Analyze the crate, responding with `503 Service Unavailable` if the
+  /// analysis times out, and `400 Bad Request` with the cause if it fails
+  /// otherwise, such as for a crate path without a `Cargo.toml`. A snapshot
+  /// is served filtered by `config`, as [`Graph::filter`] describes, with
+  /// its other settings ignored, since they only matter during analysis.
+  async fn analyze(
+    context: &Arc<Context>,
+    config: &Config,
+  ) -> Result<Graph, (StatusCode, String)> {
+    Ok(Self::analyze_cached(context, config).await?.0)
+  }
+
+  /// Like [`Server::analyze`], but also report whether the graph was reused
+  /// rather than analyzed for this request. No more than `--concurrency`
+  /// analyses run at once. A request that waited for a slot reuses the graph
+  /// of an analysis with the same settings that finished in the meantime,
+  /// and, with `--serve-stale`, a request that would wait reuses the latest
+  /// graph with the same settings instead. Node ids are carried over from
+  /// the latest graph with the same settings, per [`Graph::preserve_ids`].
+  async fn analyze_cached(
+    context: &Arc<Context>,
+    config: &Config,
+  ) -> Result<(Graph, bool), (StatusCode, String)> {
+    if let Some(snapshot) = &context.snapshot {
+      let mut graph = snapshot.clone();
+      graph.filter(config);
+      return Ok((graph, false));
+    }
+
+    let requested = Instant::now();
+
+    let key = format!("{config:?}");
+
+    let latest = |since: Option<Instant>| {
+      context.latest.lock().unwrap().as_ref().and_then(
+        |(latest_key, finished, graph)| {
+          (*latest_key == key && since.is_none_or(|since| *finished >= since))
+            .then(|| graph.clone())
+        },
+      )
+    };
+
+    let permit = match context.analyses.try_acquire() {
+      Ok(permit) => permit,
+      Err(_) => {
+        if context.serve_stale {
+          if let Some(graph) = latest(None) {
+            return Ok((graph, true));
+          }
+        }
+
+        let permit = context.analyses.acquire().await.unwrap();
+
+        if let Some(graph) = latest(Some(requested)) {
+          return Ok((graph, true));
+        }
+
+        permit
+      }
+    };
+
+    let analysis = {
+      let context = context.clone();
+      let config = config.clone();
+      task::spawn_blocking(move || context.options.analyze(&config))
+    };
+
+    let result = analysis.await.map_err(|error| {
+      (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    })?;
+
+    drop(permit);
+
+    match result {
+      Ok(mut graph) => {
+        let mut latest = context.latest.lock().unwrap();
+
+        if let Some((_, _, previous)) = latest
+          .as_ref()
+          .filter(|(latest_key, ..)| *latest_key == key)
+        {
+          graph.preserve_ids(previous);
+        }
+
+        *latest = Some((key, Instant::now(), graph.clone()));
+
+        Ok((graph, false))
+      }
+      Err(e) if e.is::<TimedOut>() => {
+        Err((StatusCode::SERVICE_UNAVAILABLE, e.to_string()))
+      }
+      Err(e) => {
+        error!("Error analyzing crate: {:?}", e);
+
+        Err((
+          StatusCode::BAD_REQUEST,
+          format!("failed to analyze crate: {e}"),
+        ))
+      }
+    }
+  }
+
+  /// The number of nodes of each kind in the graph, keyed by kind name, for
+  /// filtering by kind without fetching the graph. Kinds without any nodes
+  /// are left out unless `all` is set. Placeholders for removed nodes aren't
+  /// counted.
+  async fn kinds(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+    Query(kinds): Query<KindsQuery>,
+  ) -> Result<Json<BTreeMap<&'static str, usize>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let mut counts = BTreeMap::new();
+
+    if kinds.all == Some(true) {
+      counts.extend(
+        NodeKind::NAMES
+          .iter()
+          .filter(|&&kind| kind != NodeKind::Removed.name())
+          .map(|&kind| (kind, 0)),
+      );
+    }
+
+    for node in &graph.nodes {
+      if node.kind != NodeKind::Removed {
+        *counts.entry(node.kind.name()).or_default() += 1;
+      }
+    }
+
+    Ok(Json(counts))
+  }
+
+  /// Suggested presentation for each node kind, keyed by kind name. Colors
+  /// are hex strings and icons are lucide icon names.
+  async fn legend() -> Json<BTreeMap<&'static str, LegendEntry>> {
+    Json(
+      NodeKind::NAMES
+        .iter()
+        .map(|&kind| (kind, LegendEntry::new(kind)))
+        .collect(),
+    )
+  }
+
+  /// The version of eye and of the graph's serialized format, so clients
+  /// can detect a format they don't understand, and whether macros were
+  /// expanded. When the graph's root is a single package, whether it's
+  /// `#![no_std]` is reported too, read from its crate roots rather than
+  /// found by analyzing the crate, which would make every page load wait on
+  /// a full analysis.
+  async fn meta(
+    State(context): State<Arc<Context>>,
+  ) -> Result<Json<Value>, (StatusCode, String)> {
+    let (no_std, expanded) = match &context.snapshot {
+      Some(snapshot) => (
+        match snapshot.nodes.get(snapshot.root).map(|node| &node.kind) {
+          Some(NodeKind::Package { no_std, .. }) => Some(*no_std),
+          _ => None,
+        },
+        snapshot.expanded,
+      ),
+      None => match &context.options.crate_path {
+        Some(crate_path) => {
+          let config = context.options.config();
+
+          let packages = {
+            let crate_path = crate_path.clone();
+            let config = config.clone();
+            task::spawn_blocking(move || eye::no_std(&crate_path, &config))
+          }
+          .await
+          .map_err(|error| {
+            (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+          })?
+          .map_err(|error| {
+            (
+              StatusCode::BAD_REQUEST,
+              format!("failed to read crate metadata: {error}"),
+            )
+          })?;
+
+          let no_std = match packages.into_values().collect::<Vec<bool>>()[..] {
+            [no_std] => Some(no_std),
+            _ => None,
+          };
+
+          (no_std, config.expand)
+        }
+        None => (None, false),
+      },
+    };
+
+    let mut meta = json!({
+      "schemaVersion": Graph::SCHEMA_VERSION,
+      "version": env!("CARGO_PKG_VERSION"),
+      "expanded": expanded,
+    });
+
+    if let Some(no_std) = no_std {
+      meta["noStd"] = json!(no_std);
+    }
+
+    Ok(Json(meta))
+  }
+
+  /// The graph, as MessagePack if the `Accept` header asks for it, and JSON
+  /// otherwise. Headers report the number of nodes before any truncation,
+  /// how long analysis took, and whether the graph was reused from another
+  /// request's analysis.
+  async fn graph(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+    headers: HeaderMap,
+  ) -> Result<Response, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let fields = query.fields()?;
+
+    let build = Instant::now();
+
+    let (graph, cached) = Self::analyze_cached(&context, &config).await?;
+
+    let build_duration = build.elapsed();
+
+    let node_count = graph.nodes.len();
+
+    let format = Format::negotiate(&headers);
+
+    let shape = match fields {
+      _ if query.skeleton == Some(true) => Shape::Skeleton,
+      Some(fields) => Shape::Fields(fields),
+      None => Shape::Full,
+    };
+
+    let body = match context.max_response_bytes {
+      Some(max_bytes) => Self::truncate(graph, max_bytes, format, &shape),
+      None => Self::shaped(&graph, &shape, format, None),
+    }
+    .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))?;
+
+    Ok(
+      (
+        [
+          (CONTENT_TYPE, format.content_type().to_owned()),
+          (X_EYE_NODE_COUNT, node_count.to_string()),
+          (X_EYE_ANALYSIS_CACHED, cached.to_string()),
+          (
+            X_EYE_BUILD_DURATION_MS,
+            build_duration.as_millis().to_string(),
+          ),
+        ],
+        body,
+      )
+        .into_response(),
+    )
+  }
+
+  /// Cut `graph` down to roughly `max_bytes` when serialized in `shape`.
+  /// Nodes are visited breadth-first from the root, and each child of a kept
+  /// node is kept if its size, measured by serializing it alone in `shape`,
+  /// still fits. Every kept node's parent is therefore kept, and edges to
+  /// dropped nodes are removed. The full graph stays available from
+  /// `/api/graph/stream` and the per-node endpoints.
+  fn truncate(
+    mut graph: Graph,
+    max_bytes: usize,
+    format: Format,
+    shape: &Shape,
+  ) -> Result<Vec<u8>> {
+    let total_nodes = graph.nodes.len();
+
+    let body = Self::shaped(&graph, shape, format, Some((false, total_nodes)))?;
+
+    if body.len() <= max_bytes {
+      return Ok(body);
+    }
+
+    let mut order = vec![graph.root];
+
+    let mut i = 0;
+
+    while let Some(&id) = order.get(i) {
+      order.extend(&graph.nodes[id].children);
+      i += 1;
+    }
+
+    let mut keep = vec![false; total_nodes];
+
+    keep[graph.root] = true;
+
+    let mut bytes = 0;
+
+    for id in order {
+      if !keep[id] {
+        continue;
+      }
+
+      let node = &graph.nodes[id];
+
+      for &child in &node.children {
+        let size = match shape {
+          Shape::Full => format.serialize(&graph.nodes[child]),
+          Shape::Skeleton => {
+            format.serialize(&Self::skeleton_node(&graph.nodes[child]))
+          }
+          Shape::Fields(fields) => {
+            format.serialize(&Self::project_node(&graph.nodes[child], fields))
+          }
+        }
+        .map_or(0, |body| body.len());
+
+        if bytes + size <= max_bytes {
+          bytes += size;
+          keep[child] = true;
+        }
+      }
+    }
+
+    graph.retain(|node| keep[node.id]);
+
+    Self::shaped(&graph, shape, format, Some((true, total_nodes)))
+  }
+
+  /// Serialize `graph` in `shape`, alongside whether it was truncated and
+  /// how many nodes it had before, if `--max-response-bytes` is set.
+  fn shaped(
+    graph: &Graph,
+    shape: &Shape,
+    format: Format,
+    truncation: Option<(bool, usize)>,
+  ) -> Result<Vec<u8>> {
+    fn serialize(
+      graph: impl Serialize,
+      format: Format,
+      truncation: Option<(bool, usize)>,
+    ) -> Result<Vec<u8>> {
+      match truncation {
+        Some((truncated, total_nodes)) => format.serialize(&Truncated {
+          graph,
+          truncated,
+          total_nodes,
+        }),
+        None => format.serialize(&graph),
+      }
+    }
+
+    match shape {
+      Shape::Full => serialize(graph, format, truncation),
+      Shape::Skeleton => serialize(Self::skeleton(graph), format, truncation),
+      Shape::Fields(fields) => {
+        serialize(Self::project(graph, fields), format, truncation)
+      }
+    }
+  }
+
+  /// The containment tree alone, with each node's kind as just its name.
+  fn skeleton(graph: &Graph) -> Value {
+    json!({
+      "schemaVersion": graph.schema_version,
+      "root": graph.root,
+      "nodes": graph
+        .nodes
+        .iter()
+        .map(Self::skeleton_node)
+        .collect::<Vec<Value>>(),
+    })
+  }
+
+  fn skeleton_node(node: &Node) -> Value {
+    json!({
+      "id": node.id,
+      "name": node.name,
+      "kind": node.kind.name(),
+      "children": node.children,
+    })
+  }
+
+  /// Serialize `graph`, keeping only the named fields of each node.
+  fn project(graph: &Graph, fields: &[String]) -> Value {
+    let mut value = json!(graph);
+
+    value["nodes"] = graph
+      .nodes
+      .iter()
+      .map(|node| Self::project_node(node, fields))
+      .collect();
+
+    value
+  }
+
+  fn project_node(node: &Node, fields: &[String]) -> Value {
+    let mut value = json!(node);
+
+    if let Value::Object(node) = &mut value {
+      node.retain(|key, _| fields.contains(key));
+    }
+
+    value
+  }
+
+  async fn node(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+    headers: HeaderMap,
+  ) -> Result<Response, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let node = Self::lookup(&graph, id)?;
+
+    Ok(Self::etagged(&headers, node))
+  }
+
+  /// The dependencies declared in the manifest of the package with the given
+  /// id, with their version requirements, kinds, and feature settings.
+  async fn dependencies(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<Json<Vec<ManifestDependency>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    match &Self::lookup(&graph, id)?.kind {
+      NodeKind::Package { dependencies, .. } => Ok(Json(dependencies.clone())),
+      _ => Err((StatusCode::NOT_FOUND, format!("node {id} is not a package"))),
+    }
+  }
+
+  /// The in-crate types implementing the trait with the given id, and the
+  /// names of external types the crate implements it for.
+  async fn implementors(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<Json<Value>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let node = Self::lookup(&graph, id)?;
+
+    let NodeKind::Trait {
+      external_implementors,
+      ..
+    } = &node.kind
+    else {
+      return Err((
+        StatusCode::BAD_REQUEST,
+        format!("node {id} is not a trait"),
+      ));
+    };
+
+    let implementors = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        node
+          .edges
+          .iter()
+          .any(|edge| edge.target == id && edge.kind == EdgeKind::Implements)
+      })
+      .map(|node| node.id)
+      .collect::<Vec<NodeId>>();
+
+    Ok(Json(json!({
+      "implementors": implementors,
+      "external": external_implementors,
+    })))
+  }
+
+  /// The methods of the type with the given id, from the crate's `impl`
+  /// blocks, with trait defaults it doesn't override flagged as inherited.
+  async fn methods(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<Json<Vec<Method>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let node = Self::lookup(&graph, id)?;
+
+    match node.kind {
+      NodeKind::Struct { .. }
+      | NodeKind::Enum { .. }
+      | NodeKind::Union { .. }
+      | NodeKind::Type { .. } => Ok(Json(graph.methods(id))),
+      _ => Err((StatusCode::BAD_REQUEST, format!("node {id} is not a type"))),
+    }
+  }
+
+  async fn subtree(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+    headers: HeaderMap,
+  ) -> Result<Response, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    Self::lookup(&graph, id)?;
+
+    let subtree = graph.subtree(id).ok_or_else(|| Self::not_found(id))?;
+
+    Ok(Self::etagged(&headers, &subtree))
+  }
+
+  /// The workspace and its packages, with only the dependency edges between
+  /// packages.
+  async fn package_graph(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+    headers: HeaderMap,
+  ) -> Result<Response, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let mut graph = Self::analyze(&context, &config).await?;
+
+    graph.retain(|node| {
+      matches!(
+        node.kind,
+        NodeKind::Workspace { .. } | NodeKind::Package { .. }
+      )
+    });
+
+    Ok(Self::etagged(&headers, &graph))
+  }
+
+  async fn reexports(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<Json<BTreeMap<String, Vec<String>>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    Ok(Json(Self::analyze(&context, &config).await?.reexports))
+  }
+
+  /// Nodes whose name or one of whose `#[doc(alias)]` aliases contains the
+  /// search term, ignoring case.
+  async fn search(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+    Query(search): Query<SearchQuery>,
+  ) -> Result<Json<Vec<Value>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let term = search.q.to_lowercase();
+
+    Ok(Json(
+      graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind != NodeKind::Removed)
+        .filter_map(|node| {
+          let alias = if node.name.to_lowercase().contains(&term) {
+            None
+          } else {
+            Some(
+              node
+                .aliases
+                .iter()
+                .find(|alias| alias.to_lowercase().contains(&term))?,
+            )
+          };
+
+          Some(json!({
+            "id": node.id,
+            "name": node.name,
+            "kind": node.kind.name(),
+            "alias": alias,
+          }))
+        })
+        .collect(),
+    ))
+  }
+
+  async fn warnings(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<Json<Vec<Warning>>, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    Ok(Json(Self::analyze(&context, &config).await?.warnings))
+  }
+
+  async fn children(
+    State(context): State<Arc<Context>>,
+    Path(id): Path<NodeId>,
+    Query(query): Query<GraphQuery>,
+    headers: HeaderMap,
+  ) -> Result<Response, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let node = Self::lookup(&graph, id)?;
+
+    let children = node
+      .children
+      .iter()
+      .map(|&child| &graph.nodes[child])
+      .collect::<Vec<&Node>>();
+
+    Ok(Self::etagged(&headers, &children))
+  }
+
+  fn not_found(id: NodeId) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("no node with id {id}"))
+  }
+
+  /// The node with the given id, or `404 Not Found` if there isn't one or
+  /// its id is held by a [`NodeKind::Removed`] placeholder.
+  fn lookup(graph: &Graph, id: NodeId) -> Result<&Node, (StatusCode, String)> {
+    graph
+      .nodes
+      .get(id)
+      .filter(|node| node.kind != NodeKind::Removed)
+      .ok_or_else(|| Self::not_found(id))
+  }
+
+  /// Respond with `value` as JSON, tagged with a hash of its contents, or
+  /// with `304 Not Modified` if the request's `If-None-Match` header already
+  /// names that hash.
+  fn etagged(headers: &HeaderMap, value: &impl Serialize) -> Response {
+    let body = match serde_json::to_vec(value) {
+      Ok(body) => body,
+      Err(e) => {
+        error!("Error serializing response: {:?}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let matches = headers
+      .get(IF_NONE_MATCH)
+      .and_then(|value| value.to_str().ok())
+      .is_some_and(|value| {
+        value.split(',').map(str::trim).any(|candidate| {
+          candidate == "*" || candidate.trim_start_matches("W/") == etag
+        })
+      });
+
+    if matches {
+      return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+
+    (
+      [(CONTENT_TYPE, "application/json".to_owned()), (ETAG, etag)],
+      body,
+    )
+      .into_response()
+  }
+
+  /// Stream the graph as newline-delimited JSON: first a header line with the
+  /// root and node count, then one line per node, leaving out placeholders
+  /// for removed nodes, so that ids can skip numbers. Lines are produced into a
+  /// bounded channel, so a slow client stalls serialization rather than
+  /// buffering the whole graph.
+  async fn stream(
+    State(context): State<Arc<Context>>,
+    Query(query): Query<GraphQuery>,
+  ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let config = query.config(&context.options)?;
+
+    let graph = Self::analyze(&context, &config).await?;
+
+    let (tx, rx) = mpsc::channel::<Result<String, Infallible>>(STREAM_BUFFER);
+
+    tokio::spawn(async move {
+      let header = json!({
+        "root": graph.root,
+        "nodeCount": graph
+          .nodes
+          .iter()
+          .filter(|node| node.kind != NodeKind::Removed)
+          .count(),
+      });
+
+      if tx.send(Ok(format!("{header}\n"))).await.is_err() {
+        return;
+      }
+
+      for node in graph.nodes {
+        if node.kind == NodeKind::Removed {
+          continue;
+        }
+
+        let line = match serde_json::to_string(&node) {
+          Ok(line) => line,
+          Err(e) => {
+            error!("Error serializing node {}: {:?}", node.id, e);
+            return;
+          }
+        };
+
+        if tx.send(Ok(line + "\n")).await.is_err() {
+          return;
+        }
+      }
+    });
+
+    Ok((
+      [(CONTENT_TYPE, "application/x-ndjson")],
+      Body::from_stream(ReceiverStream::new(rx)),
+    ))
+  }
+}
+
+/// Per-request analysis settings, layered over the command line options.
+#[derive(Debug, Default, Deserialize)]
+struct GraphQuery {
+  /// Whether to trace dependencies between items, overriding
+  /// `--no-deps-edges`.
+  deps: Option<bool>,
+  /// Comma-separated node kinds to exclude.
+  exclude_kind: Option<String>,
+  /// Comma-separated node fields to include, defaulting to all of them.
+  fields: Option<String>,
+  /// Whether to list every module directly under its package, overriding
+  /// `--flatten`.
+  flatten: Option<bool>,
+  /// Regular expression that kept item names must match.
+  name_filter: Option<String>,
+  /// Whether to keep only items reachable from outside the crate, along
+  /// with their ancestors, overriding `--reachable-only`.
+  reachable: Option<bool>,
+  /// Whether to respond with only each node's id, name, kind, and children,
+  /// for rendering the tree before fetching details from `/api/node/:id`.
+  skeleton: Option<bool>,
+  /// The least visible items to keep, along with their ancestors: `public`
+  /// for `pub` items, `crate` to also keep `pub(crate)` items, or `all`.
+  visibility: Option<String>,
+}
+
+impl GraphQuery {
+  fn config(&self, options: &Options) -> Result<Config, (StatusCode, String)> {
+    let mut config = options.config();
+
+    if let Some(kinds) = &self.exclude_kind {
+      for kind in kinds.split(',').filter(|kind| !kind.is_empty()) {
+        NodeKind::validate(kind)
+          .map_err(|error| (StatusCode::BAD_REQUEST, error.to_string()))?;
+        config.exclude_kinds.push(kind.to_owned());
+      }
+    }
+
+    if let Some(deps) = self.deps {
+      config.no_deps_edges = !deps;
+    }
+
+    if let Some(flatten) = self.flatten {
+      config.flatten = flatten;
+    }
+
+    if let Some(name_filter) = &self.name_filter {
+      config.name_filter = Some(Regex::new(name_filter).map_err(|error| {
+        (
+          StatusCode::BAD_REQUEST,
+          format!("invalid name filter: {error}"),
+        )
+      })?);
+    }
+
+    if let Some(reachable) = self.reachable {
+      config.reachable_only = reachable;
+    }
+
+    config.visibility = match self.visibility.as_deref() {
+      None | Some("all") => None,
+      Some("public") => Some(Visibility::Public),
+      Some("crate") => Some(Visibility::Crate),
+      Some(visibility) => {
+        return Err((
+          StatusCode::BAD_REQUEST,
+          format!(
+            "invalid visibility `{visibility}`, expected one of: public, \
+             crate, all"
+          ),
+        ))
+      }
+    };
+
+    Ok(config)
+  }
+
+  fn fields(&self) -> Result<Option<Vec<String>>, (StatusCode, String)> {
+    let Some(fields) = &self.fields else {
+      return Ok(None);
+    };
+
+    let Value::Object(known) = json!(Node::default()) else {
+      unreachable!();
+    };
+
+    let fields = fields
+      .split(',')
+      .filter(|field| !field.is_empty())
+      .map(str::to_owned)
+      .collect::<Vec<String>>();
+
+    if let Some(unknown) =
+      fields.iter().find(|field| !known.contains_key(*field))
+    {
+      return Err((
+        StatusCode::BAD_REQUEST,
+        format!(
+          "unknown node field `{unknown}`, expected one of: {}",
+          known.keys().cloned().collect::<Vec<String>>().join(", ")
+        ),
+      ));
+    }
+
+    Ok(Some(fields))
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct KindsQuery {
+  /// Whether to include kinds without any nodes, with a count of zero.
+  all: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+  /// The text to look for in node names and aliases.
+  q: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LegendEntry {
+  color: &'static str,
+  icon: &'static str,
+}
+
+impl LegendEntry {
+  fn new(kind: &str) -> Self {
+    let (color, icon) = match kind {
+      "workspace" => ("#FF6B6B", "boxes"),
+      "package" => ("#4ECDC4", "package"),
+      "target" => ("#F7B267", "target"),
+      "module" => ("#97C2FC", "folder"),
+      "struct" => ("#FFCCCB", "braces"),
+      "enum" => ("#90EE90", "list"),
+      "union" => ("#FFE4B5", "combine"),
+      "function" => ("#FFD700", "function-square"),
+      "const" => ("#FFA07A", "hash"),
+      "macro" => ("#FF69B4", "wand"),
+      "static" => ("#8FBC8F", "pin"),
+      "trait" => ("#ADD8E6", "puzzle"),
+      "traitAlias" => ("#E6E6FA", "link"),
+      "type" => ("#F08080", "type"),
+      "impl" => ("#B0C4DE", "blocks"),
+      "verbatim" => ("#DCDCDC", "file-code"),
+      "external" => ("#C0C0C0", "external-link"),
+      "removed" => ("#F5F5F5", "circle-off"),
+      _ => ("#D3D3D3", "circle-help"),
+    };
+
+    Self { color, icon }
+  }
+}
@@ -0,0 +1,254 @@
+use super::*;
+
+const HIGHEST_CHURN: usize = 5;
+
+const LARGEST_STRUCTS: usize = 5;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Stats {}
+
+impl Stats {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let graph = options.analyze(&options.config())?;
+
+    let mut kinds = BTreeMap::<&str, usize>::new();
+
+    for node in &graph.nodes {
+      *kinds.entry(node.kind.name()).or_default() += 1;
+    }
+
+    println!("{} {}", options.color.bold("Nodes:"), graph.nodes.len());
+
+    for (kind, count) in kinds {
+      println!("  {kind}: {count}");
+    }
+
+    println!(
+      "{} {}",
+      options.color.bold("Edges:"),
+      graph
+        .nodes
+        .iter()
+        .map(|node| node.edges.len())
+        .sum::<usize>()
+    );
+
+    let reachable = graph
+      .nodes
+      .iter()
+      .filter(|node| node.publicly_reachable)
+      .collect::<Vec<&Node>>();
+
+    let exposed = reachable
+      .iter()
+      .filter(|node| node.visibility != Visibility::Public)
+      .collect::<Vec<&&Node>>();
+
+    println!(
+      "{} {} ({} exposed without being `pub`)",
+      options.color.bold("Publicly reachable items:"),
+      reachable.len(),
+      exposed.len()
+    );
+
+    for node in exposed {
+      println!("  {}", node.name);
+    }
+
+    let unsafe_functions = graph
+      .nodes
+      .iter()
+      .filter_map(|node| match node.kind {
+        NodeKind::Function {
+          unsafe_block_count, ..
+        } if unsafe_block_count > 0 => Some((node, unsafe_block_count)),
+        _ => None,
+      })
+      .collect::<Vec<(&Node, u32)>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Functions using unsafe:"),
+      unsafe_functions.len()
+    );
+
+    for (node, count) in unsafe_functions {
+      println!("  {} ({count} unsafe blocks)", node.name);
+    }
+
+    let modules = graph.nearest(|kind| matches!(kind, NodeKind::Module { .. }));
+
+    let unsafe_impls = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          NodeKind::Impl {
+            is_unsafe: true,
+            ..
+          }
+        )
+      })
+      .map(|node| {
+        let file =
+          match modules[node.id].map(|module| &graph.nodes[module].kind) {
+            Some(NodeKind::Module { path, .. }) => path.display().to_string(),
+            _ => String::new(),
+          };
+
+        (node, file)
+      })
+      .collect::<Vec<(&Node, String)>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Unsafe impls:"),
+      unsafe_impls.len()
+    );
+
+    for (node, file) in unsafe_impls {
+      println!("  {} ({file}:{})", node.name, node.line.unwrap_or(1));
+    }
+
+    let unsafe_traits = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          NodeKind::Trait {
+            is_unsafe: true,
+            ..
+          }
+        )
+      })
+      .collect::<Vec<&Node>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Unsafe traits:"),
+      unsafe_traits.len()
+    );
+
+    for node in unsafe_traits {
+      println!("  {}", node.name);
+    }
+
+    let placeholder_functions = graph
+      .nodes
+      .iter()
+      .filter_map(|node| match node.kind {
+        NodeKind::Function {
+          panic_count,
+          todo_count,
+          unimplemented_count,
+          ..
+        } if panic_count + todo_count + unimplemented_count > 0 => {
+          Some((node, panic_count, todo_count, unimplemented_count))
+        }
+        _ => None,
+      })
+      .collect::<Vec<(&Node, u32, u32, u32)>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Functions with placeholders:"),
+      placeholder_functions.len()
+    );
+
+    for (node, panic_count, todo_count, unimplemented_count) in
+      placeholder_functions
+    {
+      println!(
+        "  {} ({panic_count} panic, {todo_count} to-do, \
+         {unimplemented_count} unimplemented)",
+        node.name
+      );
+    }
+
+    let recursive_functions = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          NodeKind::Function {
+            is_recursive: true,
+            ..
+          }
+        )
+      })
+      .collect::<Vec<&Node>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Recursive functions:"),
+      recursive_functions.len()
+    );
+
+    for node in recursive_functions {
+      println!("  {}", node.name);
+    }
+
+    let recursive_types = graph
+      .nodes
+      .iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          NodeKind::Struct {
+            self_referential: true,
+            ..
+          } | NodeKind::Enum {
+            self_referential: true,
+            ..
+          }
+        )
+      })
+      .collect::<Vec<&Node>>();
+
+    println!(
+      "{} {}",
+      options.color.bold("Self-referential types:"),
+      recursive_types.len()
+    );
+
+    for node in recursive_types {
+      println!("  {}", node.name);
+    }
+
+    let mut structs = graph
+      .nodes
+      .iter()
+      .filter(|node| matches!(node.kind, NodeKind::Struct { .. }))
+      .filter_map(|node| Some((node, node.member_count?)))
+      .collect::<Vec<(&Node, usize)>>();
+
+    structs.sort_by_key(|&(_, count)| Reverse(count));
+
+    println!("{}", options.color.bold("Largest structs:"));
+
+    for (node, count) in structs.into_iter().take(LARGEST_STRUCTS) {
+      println!("  {} ({count} fields)", node.name);
+    }
+
+    let mut churned = graph
+      .nodes
+      .iter()
+      .filter_map(|node| Some((node, node.churn?)))
+      .collect::<Vec<(&Node, u32)>>();
+
+    if !churned.is_empty() {
+      churned.sort_by_key(|&(_, churn)| Reverse(churn));
+
+      println!("{}", options.color.bold("Highest churn:"));
+
+      for (node, churn) in churned.into_iter().take(HIGHEST_CHURN) {
+        println!("  {} ({churn} commits)", node.name);
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Validate {}
+
+impl Validate {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let graph = options.analyze(&options.config())?;
+
+    let files = graph
+      .nodes
+      .iter()
+      .filter_map(|node| match &node.kind {
+        NodeKind::Module { path, .. } => Some(path),
+        _ => None,
+      })
+      .collect::<BTreeSet<&PathBuf>>();
+
+    let (failures, warnings) = graph
+      .warnings
+      .iter()
+      .partition::<Vec<&Warning>, _>(|warning| {
+        matches!(
+          warning.category,
+          WarningCategory::Parse | WarningCategory::Read
+        )
+      });
+
+    println!("{} {}", options.color.bold("Files:"), files.len());
+
+    println!("{} {}", options.color.bold("Failures:"), failures.len());
+
+    for failure in &failures {
+      println!("  {failure}");
+    }
+
+    println!("{} {}", options.color.bold("Warnings:"), warnings.len());
+
+    for warning in warnings {
+      println!("  {warning}");
+    }
+
+    if !failures.is_empty() {
+      bail!("validation found {} problem(s)", failures.len());
+    }
+
+    Ok(())
+  }
+}
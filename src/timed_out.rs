@@ -0,0 +1,14 @@
+use super::*;
+
+/// The error returned when analysis runs past [`Config::timeout`]. No partial
+/// graph is produced.
+#[derive(Debug)]
+pub struct TimedOut(pub Duration);
+
+impl Display for TimedOut {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "analysis timed out after {}s", self.0.as_secs_f64())
+  }
+}
+
+impl std::error::Error for TimedOut {}
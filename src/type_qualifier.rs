@@ -0,0 +1,52 @@
+use super::*;
+
+/// Replaces single-segment type paths, such as `Config<T>`, whose names are
+/// keys of `paths` with the paths they map to, such as
+/// `crate::server::Config<T>`, keeping their generic arguments.
+pub(crate) struct TypeQualifier<'a> {
+  paths: &'a HashMap<&'a str, String>,
+}
+
+impl<'a> TypeQualifier<'a> {
+  /// Qualify the names in `type_name`, rendered again, or return it as it is
+  /// if it doesn't parse as a type.
+  pub(crate) fn qualify(
+    paths: &'a HashMap<&'a str, String>,
+    type_name: &str,
+  ) -> String {
+    let Ok(mut ty) = parse_str::<Type>(type_name) else {
+      return type_name.to_owned();
+    };
+
+    Self { paths }.visit_type_mut(&mut ty);
+
+    render::ty(&ty)
+  }
+}
+
+impl VisitMut for TypeQualifier<'_> {
+  fn visit_type_path_mut(&mut self, i: &mut TypePath) {
+    syn::visit_mut::visit_type_path_mut(self, i);
+
+    if i.qself.is_some()
+      || i.path.leading_colon.is_some()
+      || i.path.segments.len() != 1
+    {
+      return;
+    }
+
+    let segment = &i.path.segments[0];
+
+    let Some(Ok(mut path)) = self
+      .paths
+      .get(segment.ident.to_string().as_str())
+      .map(|path| parse_str::<syn::Path>(path))
+    else {
+      return;
+    };
+
+    path.segments.last_mut().unwrap().arguments = segment.arguments.clone();
+
+    i.path = path;
+  }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+/// Ordered from most to least visible, so that `pub` is the least.
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  Default,
+  Deserialize,
+  Eq,
+  Ord,
+  PartialEq,
+  PartialOrd,
+  Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Visibility {
+  /// `pub`
+  Public,
+  /// `pub(crate)`
+  Crate,
+  /// `pub(super)`, `pub(self)`, or `pub(in path)`
+  Restricted,
+  /// No visibility modifier.
+  #[default]
+  Private,
+}
+
+impl Visibility {
+  /// The visibility's name, as it appears when serialized.
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Public => "public",
+      Self::Crate => "crate",
+      Self::Restricted => "restricted",
+      Self::Private => "private",
+    }
+  }
+}
+
+impl From<&syn::Visibility> for Visibility {
+  fn from(visibility: &syn::Visibility) -> Self {
+    match visibility {
+      syn::Visibility::Public(_) => Self::Public,
+      syn::Visibility::Crate(_) => Self::Crate,
+      syn::Visibility::Restricted(restricted)
+        if restricted.path.is_ident("crate") =>
+      {
+        Self::Crate
+      }
+      syn::Visibility::Restricted(_) => Self::Restricted,
+      syn::Visibility::Inherited => Self::Private,
+    }
+  }
+}
@@ -0,0 +1,18 @@
+use super::*;
+
+/// Something the analyzer couldn't handle, but that didn't stop it from
+/// producing a graph.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Warning {
+  pub category: WarningCategory,
+  pub message: String,
+  /// The file or directory the warning concerns.
+  pub path: PathBuf,
+}
+
+impl Display for Warning {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}: {}", self.path.display(), self.message)
+  }
+}
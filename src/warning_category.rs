@@ -0,0 +1,30 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WarningCategory {
+  /// A pattern in the annotations file matched no items.
+  Annotation,
+  /// A module defines more than one item with the same name, so references
+  /// to it may be resolved to the wrong one.
+  Duplicate,
+  /// `cargo expand` failed for a target, so its source was analyzed as
+  /// written.
+  Expand,
+  /// Changes since a git revision couldn't be found, so no node is marked
+  /// as changed.
+  Git,
+  /// A source file couldn't be parsed, so its items are missing.
+  Parse,
+  /// A source file couldn't be read.
+  Read,
+  /// A source file's module name couldn't be derived from its path.
+  Path,
+  /// A source file was larger than [`Config::max_file_size`], so it was
+  /// skipped.
+  Size,
+  /// An item could only be kept as tokens, so its contents aren't analyzed.
+  Verbatim,
+  /// A source directory couldn't be fully traversed.
+  Walk,
+}